@@ -0,0 +1,57 @@
+//! Exercise every RGB channel of an LP50xx on a Linux I2C bus, lighting each red, green and blue in turn
+//!
+//! ```text
+//! cargo run --example linux --features linux -- /dev/i2c-1 LP5012
+//! ```
+
+use linux_embedded_hal::Delay;
+use lp50xx::Model;
+use std::env;
+use std::error::Error;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+fn parse_model(name: &str) -> Option<Model> {
+    match name.to_ascii_uppercase().as_str() {
+        "LP5009" => Some(Model::LP5009),
+        "LP5012" => Some(Model::LP5012),
+        "LP5018" => Some(Model::LP5018),
+        "LP5024" => Some(Model::LP5024),
+        "LP5030" => Some(Model::LP5030),
+        "LP5036" => Some(Model::LP5036),
+        _ => None,
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    let (path, model) = match (args.get(1), args.get(2).and_then(|name| parse_model(name))) {
+        (Some(path), Some(model)) => (path, model),
+        _ => {
+            eprintln!(
+                "usage: {} <i2c device> <LP5009|LP5012|LP5018|LP5024|LP5030|LP5036>",
+                args[0]
+            );
+            process::exit(2);
+        }
+    };
+
+    let controller = lp50xx::linux::open(model, path)?;
+    let mut controller = controller.into_color_mode().enable(&mut Delay)?;
+    controller.configure(true, false, true, true, false, false)?;
+
+    for channel in 1..=model.rgb_channel_count() {
+        for (name, color) in [
+            ("red", [0xFF, 0, 0]),
+            ("green", [0, 0xFF, 0]),
+            ("blue", [0, 0, 0xFF]),
+        ] {
+            println!("channel {} {}", channel, name);
+            controller.set(channel, (0xFF, color))?;
+            thread::sleep(Duration::from_millis(300));
+        }
+        controller.set(channel, (0, [0, 0, 0]))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,1067 @@
+//! Non-blocking animations driven by a periodic `tick`
+//!
+//! Transitions are enqueued on an `Animator`, which is then ticked with the elapsed time from a timer
+//! interrupt or a cooperative task. Each tick only writes the outputs whose value has changed.
+//!
+//! ```ignore
+//! let mut animator: Animator<(u8, [u8; 3]), 4> = Animator::new();
+//! animator.fade(1, (0xFF, [0, 0, 0]), (0xFF, [255, 0, 0]), 1000)?;
+//! animator.fade_with(3, (0xFF, [0, 0, 0]), (0xFF, [0, 0, 255]), 1000, Easing::EaseInOut)?;
+//! animator.sweep(2, 0xFF, 0, 360, 5000)?;
+//! // Every 10ms
+//! animator.tick(&mut controller, 10)?;
+//! ```
+
+use crate::color::hsv_to_rgb;
+use crate::{ColorMode, Enabled, Error, LP50xx, Lp50xxInterface, MonochromaticMode};
+use core::convert::TryFrom;
+use embedded_hal::digital::OutputPin;
+
+/// Output values which can be interpolated, the luminosity in monochromatic mode
+/// and the brightness and RGB values in color mode
+pub trait Blend: Copy + PartialEq {
+    /// Interpolate between two values
+    /// * `from` - the value at fraction 0
+    /// * `to` - the value at fraction 255
+    /// * `fraction` - the position between the values
+    fn blend(from: Self, to: Self, fraction: u8) -> Self;
+}
+
+impl Blend for u8 {
+    fn blend(from: Self, to: Self, fraction: u8) -> Self {
+        (from as i32 + (to as i32 - from as i32) * fraction as i32 / 255) as u8
+    }
+}
+
+impl Blend for (u8, [u8; 3]) {
+    fn blend((from, from_color): Self, (to, to_color): Self, fraction: u8) -> Self {
+        let color = |index: usize| u8::blend(from_color[index], to_color[index], fraction);
+        (
+            u8::blend(from, to, fraction),
+            [color(0), color(1), color(2)],
+        )
+    }
+}
+
+/// Breathing curve, rising from 0 to 255 and back over the period. A triangle wave is eased with a
+/// smoothstep, which closely approximates a sine wave using integer math only
+/// * `phase` - Position within the period
+/// * `period` - Length of the period
+pub(crate) fn breath(phase: u32, period: u32) -> u8 {
+    if period == 0 {
+        return 0;
+    }
+    let triangle = ((phase % period) as u64 * 510 / period as u64) as u32;
+    let t = if triangle > 255 {
+        510 - triangle
+    } else {
+        triangle
+    };
+    (t * t * (765 - 2 * t) / (255 * 255)) as u8
+}
+
+/// Easing curve of a transition, mapping the elapsed fraction of the transition onto the fraction of
+/// the distance travelled. Every curve is evaluated in fixed point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Start slowly and accelerate, along a quadratic curve
+    EaseIn,
+    /// Start quickly and decelerate, along a quadratic curve
+    EaseOut,
+    /// Accelerate and then decelerate, along a smoothstep curve
+    EaseInOut,
+    /// Accelerate and then decelerate, along a cubic curve
+    Cubic,
+    /// Accelerate and then decelerate, along an exponential curve
+    Exponential,
+}
+
+impl Easing {
+    /// Map the elapsed fraction onto the fraction of the distance travelled
+    /// * `fraction` - the elapsed fraction, 0 at the start and 255 once complete
+    pub fn apply(self, fraction: u8) -> u8 {
+        let t = fraction as u32;
+        let eased = match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t / 255,
+            Easing::EaseOut => 255 - (255 - t) * (255 - t) / 255,
+            Easing::EaseInOut => t * t * (765 - 2 * t) / (255 * 255),
+            Easing::Cubic if t < 128 => 4 * t * t * t / (255 * 255),
+            Easing::Cubic => 255 - 4 * (255 - t) * (255 - t) * (255 - t) / (255 * 255),
+            Easing::Exponential if t < 128 => exponential_in(2 * t) / 2,
+            Easing::Exponential => 255 - exponential_in(2 * (255 - t)) / 2,
+        };
+        eased as u8
+    }
+}
+
+/// Exponential ease in, rising from 2^-10 to 1 of full scale. Powers of two are interpolated linearly
+/// * `t` - the elapsed fraction, from 0 to 255
+fn exponential_in(t: u32) -> u32 {
+    let exponent = 10 * t;
+    let (whole, fraction) = (exponent / 255, exponent % 255);
+    ((1 << whole) * (255 + fraction) / 255) * 255 / 1024
+}
+
+/// Drivers whose outputs can be animated
+pub trait Animate {
+    /// The output value type
+    type Value: Blend;
+
+    /// Set an output to a value
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `value` - the output value
+    fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error>;
+}
+
+impl<IFACE, EN> Animate for LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    type Value = (u8, [u8; 3]);
+
+    fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error> {
+        let channel = u8::try_from(output).map_err(|_| Error::InvalidChannel)?;
+        self.set(channel, value)
+    }
+}
+
+impl<IFACE, EN> Animate for LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    type Value = u8;
+
+    fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error> {
+        self.set(output, value)
+    }
+}
+
+/// The asynchronous counterpart of `Animate`, enabled with the `async` feature
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AnimateAsync {
+    /// The output value type
+    type Value: Blend;
+
+    /// Set an output to a value without blocking the executor
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `value` - the output value
+    async fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error>;
+}
+
+#[cfg(feature = "async")]
+impl<IFACE, EN> AnimateAsync for LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: crate::Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    type Value = (u8, [u8; 3]);
+
+    async fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error> {
+        let channel = u8::try_from(output).map_err(|_| Error::InvalidChannel)?;
+        self.set_async(channel, value).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<IFACE, EN> AnimateAsync for LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: crate::Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    type Value = u8;
+
+    async fn apply(&mut self, output: u16, value: Self::Value) -> Result<(), Error> {
+        self.set_async(output, value).await
+    }
+}
+
+/// The effect played by a transition
+#[derive(Clone, Copy)]
+enum Effect<V> {
+    /// Interpolate between two values
+    Fade { from: V, to: V },
+    /// Interpolate the hue between two angles, mapping the brightness and hue into the output value
+    Sweep {
+        brightness: u8,
+        from: u16,
+        to: u16,
+        map: fn(u8, u16) -> V,
+    },
+    /// Breathe between two values, repeating every period
+    Breathe { min: V, max: V, period: u32 },
+    /// Alternate between two values, repeating until cancelled when `forever` is set
+    Blink {
+        on: V,
+        off: V,
+        on_ms: u32,
+        off_ms: u32,
+        forever: bool,
+    },
+}
+
+/// A transition of a single output
+#[derive(Clone, Copy)]
+struct Transition<V> {
+    /// The channel in color mode, or the LED index in monochromatic mode
+    output: u16,
+    effect: Effect<V>,
+    /// Duration of the transition in milliseconds
+    duration: u32,
+    /// Time elapsed since the transition started in milliseconds
+    elapsed: u32,
+    /// The last value written to the output
+    last: Option<V>,
+    /// Easing curve of fades and sweeps
+    easing: Easing,
+}
+
+impl<V: Blend> Transition<V> {
+    /// Position of the transition, 0 at the start and 255 once complete
+    fn fraction(&self) -> u8 {
+        if self.elapsed >= self.duration {
+            return 255;
+        }
+        (self.elapsed as u64 * 255 / self.duration as u64) as u8
+    }
+
+    /// The output value at the current position
+    fn value(&self) -> V {
+        let fraction = self.easing.apply(self.fraction());
+        match self.effect {
+            Effect::Fade { from, to } => V::blend(from, to, fraction),
+            Effect::Sweep {
+                brightness,
+                from,
+                to,
+                map,
+            } => {
+                let hue = from as i32 + (to as i32 - from as i32) * fraction as i32 / 255;
+                map(brightness, hue as u16)
+            }
+            Effect::Breathe { min, max, period } => {
+                V::blend(min, max, breath(self.elapsed, period))
+            }
+            Effect::Blink { off, .. } if self.is_complete() => off,
+            Effect::Blink {
+                on,
+                off,
+                on_ms,
+                off_ms,
+                ..
+            } => {
+                if self.elapsed % on_ms.saturating_add(off_ms).max(1) < on_ms {
+                    on
+                } else {
+                    off
+                }
+            }
+        }
+    }
+
+    /// Period of effects which repeat until cancelled
+    fn period(&self) -> Option<u32> {
+        match self.effect {
+            Effect::Breathe { period, .. } => Some(period),
+            Effect::Blink {
+                on_ms,
+                off_ms,
+                forever: true,
+                ..
+            } => Some(on_ms.saturating_add(off_ms)),
+            _ => None,
+        }
+    }
+
+    /// Advance the transition, effects which repeat until cancelled wrap around their period
+    /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+    fn advance(&mut self, elapsed_ms: u32) {
+        self.elapsed = self.elapsed.saturating_add(elapsed_ms);
+        if let Some(period) = self.period() {
+            self.elapsed %= period.max(1);
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.period().is_none() && self.elapsed >= self.duration
+    }
+}
+
+/// A single frame of a pattern
+pub struct Frame<V: 'static> {
+    /// The outputs set at the start of the frame, the channel in color mode or the LED index in
+    /// monochromatic mode along with its value
+    pub outputs: &'static [(u16, V)],
+    /// Time the frame is held in milliseconds
+    pub duration_ms: u32,
+}
+
+/// A sequence of frames which can be stored in flash as a `const` and played back by an `Animator`
+///
+/// ```ignore
+/// const ERROR: Pattern<u8> = Pattern {
+///     frames: &[
+///         Frame { outputs: &[(1, 0xFF), (2, 0x00)], duration_ms: 200 },
+///         Frame { outputs: &[(1, 0x00), (2, 0xFF)], duration_ms: 200 },
+///     ],
+///     repeat: true,
+/// };
+/// animator.play(&ERROR);
+/// ```
+pub struct Pattern<V: 'static> {
+    /// The frames, played in order
+    pub frames: &'static [Frame<V>],
+    /// Restart from the first frame after the last, until stopped
+    pub repeat: bool,
+}
+
+/// Playback position of a pattern
+struct Playback<V: 'static> {
+    pattern: &'static Pattern<V>,
+    /// Index of the current frame
+    frame: usize,
+    /// Time elapsed since the current frame started in milliseconds
+    elapsed: u32,
+    /// Whether the outputs of the current frame have been written
+    written: bool,
+}
+
+/// Advance the playback, shared by the blocking and asynchronous animators
+macro_rules! playback_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Advance the playback, writing the outputs of every frame entered.
+            /// Returns false once the last frame of a pattern which does not repeat has elapsed
+            /// * `driver` - the LP50xx driving the outputs
+            /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+            fn tick / tick_async<D>(&mut self, driver: &mut D, elapsed_ms: u32) -> Result<bool, Error>
+            where
+                D: Animate<Value = V>,
+            {
+                let frames = self.pattern.frames;
+                // A repeating pattern without any duration would never yield
+                let repeat = self.pattern.repeat && frames.iter().any(|frame| frame.duration_ms > 0);
+                self.elapsed = self.elapsed.saturating_add(elapsed_ms);
+
+                while let Some(frame) = frames.get(self.frame) {
+                    if !self.written {
+                        for (output, value) in frame.outputs {
+                            io!($variant, driver.apply(*output, *value))?;
+                        }
+                        self.written = true;
+                    }
+                    if self.elapsed < frame.duration_ms {
+                        return Ok(true);
+                    }
+
+                    self.elapsed -= frame.duration_ms;
+                    self.written = false;
+                    self.frame += 1;
+                    if self.frame == frames.len() && repeat {
+                        self.frame = 0;
+                    }
+                }
+                Ok(false)
+            }
+        }
+    };
+}
+
+impl<V: Blend> Playback<V> {
+    playback_methods!(blocking);
+}
+
+/// A keyframe of a timeline
+pub struct Keyframe<V: 'static> {
+    /// Time of the keyframe from the start of the timeline in milliseconds
+    pub time_ms: u32,
+    /// The targets reached at the keyframe, the channel in color mode or the LED index in monochromatic
+    /// mode along with its value
+    pub outputs: &'static [(u16, V)],
+    /// Easing curve of the transition of each output from its previous keyframe
+    pub easing: Easing,
+}
+
+/// A choreographed sequence of several outputs, defined by keyframes which can be stored in flash as a
+/// `const` and played back by an `Animator`. Each output is interpolated between the keyframes holding
+/// it, is not written before its first keyframe and holds its value after its last
+///
+/// ```ignore
+/// const BOOT: Timeline<u8> = Timeline {
+///     keyframes: &[
+///         Keyframe { time_ms: 0, outputs: &[(1, 0x00), (2, 0x00)], easing: Easing::Linear },
+///         Keyframe { time_ms: 300, outputs: &[(1, 0xFF)], easing: Easing::EaseOut },
+///         Keyframe { time_ms: 600, outputs: &[(1, 0x00), (2, 0xFF)], easing: Easing::EaseInOut },
+///     ],
+///     repeat: false,
+/// };
+/// animator.play_timeline(&BOOT);
+/// ```
+pub struct Timeline<V: 'static> {
+    /// The keyframes, in ascending order of time
+    pub keyframes: &'static [Keyframe<V>],
+    /// Restart from the beginning after the last keyframe, until stopped
+    pub repeat: bool,
+}
+
+impl<V: Blend> Timeline<V> {
+    /// Duration of the timeline in milliseconds, the time of the last keyframe
+    pub fn duration(&self) -> u32 {
+        self.keyframes.last().map_or(0, |keyframe| keyframe.time_ms)
+    }
+
+    /// The value of an output at a time, None before its first keyframe
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `time_ms` - Time from the start of the timeline in milliseconds
+    pub fn value(&self, output: u16, time_ms: u32) -> Option<V> {
+        let mut from: Option<(u32, V)> = None;
+        for keyframe in self.keyframes {
+            let to = match keyframe.outputs.iter().find(|(o, _)| *o == output) {
+                Some((_, to)) => *to,
+                None => continue,
+            };
+            if keyframe.time_ms <= time_ms {
+                from = Some((keyframe.time_ms, to));
+                continue;
+            }
+
+            let (start, value) = from?;
+            let elapsed = (time_ms - start) as u64;
+            let fraction = (elapsed * 255 / (keyframe.time_ms - start) as u64) as u8;
+            return Some(V::blend(value, to, keyframe.easing.apply(fraction)));
+        }
+        from.map(|(_, value)| value)
+    }
+
+    /// Whether the output first appears in the keyframe, so each output is visited once
+    /// * `index` - Index of the keyframe
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    fn is_first(&self, index: usize, output: u16) -> bool {
+        !self.keyframes[..index]
+            .iter()
+            .any(|keyframe| keyframe.outputs.iter().any(|(o, _)| *o == output))
+    }
+}
+
+/// Playback position of a timeline
+struct TimelinePlayback<V: 'static> {
+    timeline: &'static Timeline<V>,
+    /// Time elapsed since the start of the timeline in milliseconds
+    elapsed: u32,
+    /// Whether the outputs have been written
+    written: bool,
+}
+
+/// Advance the timeline, shared by the blocking and asynchronous animators
+macro_rules! timeline_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Advance the timeline, writing the outputs whose value has changed.
+            /// Returns false once the last keyframe of a timeline which does not repeat has been reached
+            /// * `driver` - the LP50xx driving the outputs
+            /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+            fn tick / tick_async<D>(&mut self, driver: &mut D, elapsed_ms: u32) -> Result<bool, Error>
+            where
+                D: Animate<Value = V>,
+            {
+                let timeline = self.timeline;
+                let duration = timeline.duration();
+                let previous = self.elapsed;
+                let elapsed = previous.saturating_add(elapsed_ms);
+                self.elapsed = if timeline.repeat && duration > 0 {
+                    elapsed % duration
+                } else {
+                    elapsed.min(duration)
+                };
+
+                for (index, keyframe) in timeline.keyframes.iter().enumerate() {
+                    for (output, _) in keyframe.outputs {
+                        if !timeline.is_first(index, *output) {
+                            continue;
+                        }
+                        let value = match timeline.value(*output, self.elapsed) {
+                            Some(value) => value,
+                            None => continue,
+                        };
+                        if !self.written || timeline.value(*output, previous) != Some(value) {
+                            io!($variant, driver.apply(*output, value))?;
+                        }
+                    }
+                }
+                self.written = true;
+                Ok(timeline.repeat || elapsed < duration)
+            }
+        }
+    };
+}
+
+impl<V: Blend> TimelinePlayback<V> {
+    timeline_methods!(blocking);
+}
+
+/// Advance the animator, shared by the blocking and asynchronous animators
+macro_rules! animator_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Advance every transition and write the outputs whose value has changed.
+            /// Completed transitions are removed once their final value is written
+            /// * `driver` - the LP50xx driving the outputs
+            /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+            pub fn tick / tick_async<D>(&mut self, driver: &mut D, elapsed_ms: u32) -> Result<(), Error>
+            where
+                D: Animate<Value = V>,
+            {
+                if let Some(playback) = &mut self.playback {
+                    if !io!($variant, playback.tick(driver, elapsed_ms))? {
+                        self.playback = None;
+                    }
+                }
+                if let Some(timeline) = &mut self.timeline {
+                    if !io!($variant, timeline.tick(driver, elapsed_ms))? {
+                        self.timeline = None;
+                    }
+                }
+
+                for slot in self.slots.iter_mut() {
+                    if let Some(transition) = slot {
+                        transition.advance(elapsed_ms);
+
+                        let value = transition.value();
+                        if transition.last != Some(value) {
+                            io!($variant, driver.apply(transition.output, value))?;
+                            transition.last = Some(value);
+                        }
+                        if transition.is_complete() {
+                            *slot = None;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Runs up to `N` concurrent transitions, one per output
+pub struct Animator<V: 'static, const N: usize> {
+    slots: [Option<Transition<V>>; N],
+    /// Pattern being played back
+    playback: Option<Playback<V>>,
+    /// Timeline being played back
+    timeline: Option<TimelinePlayback<V>>,
+}
+
+impl<V: Blend, const N: usize> Default for Animator<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Blend, const N: usize> Animator<V, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: [None; N],
+            playback: None,
+            timeline: None,
+        }
+    }
+
+    /// Fade an output between two values
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `from` - the starting value
+    /// * `to` - the final value
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    pub fn fade(&mut self, output: u16, from: V, to: V, duration_ms: u32) -> Result<(), Error> {
+        self.fade_with(output, from, to, duration_ms, Easing::Linear)
+    }
+
+    /// Fade an output between two values along an easing curve
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `from` - the starting value
+    /// * `to` - the final value
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    /// * `easing` - the easing curve
+    pub fn fade_with(
+        &mut self,
+        output: u16,
+        from: V,
+        to: V,
+        duration_ms: u32,
+        easing: Easing,
+    ) -> Result<(), Error> {
+        self.start(output, Effect::Fade { from, to }, duration_ms, easing)
+    }
+
+    /// Breathe an output between two values until cancelled
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `period_ms` - Duration of a single breath in milliseconds
+    /// * `min` - the value at the start and end of each breath
+    /// * `max` - the value in the middle of each breath
+    pub fn breathe(&mut self, output: u16, period_ms: u32, min: V, max: V) -> Result<(), Error> {
+        self.start(
+            output,
+            Effect::Breathe {
+                min,
+                max,
+                period: period_ms,
+            },
+            0,
+            Easing::Linear,
+        )
+    }
+
+    /// Blink an output, several outputs may blink concurrently at independent rates
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `on` - the value while on
+    /// * `off` - the value while off, which is also the final value
+    /// * `on_ms` - Time spent on in milliseconds
+    /// * `off_ms` - Time spent off in milliseconds
+    /// * `repeats` - Number of blinks, or 0 to blink until cancelled
+    pub fn blink(
+        &mut self,
+        output: u16,
+        on: V,
+        off: V,
+        on_ms: u32,
+        off_ms: u32,
+        repeats: u16,
+    ) -> Result<(), Error> {
+        let effect = Effect::Blink {
+            on,
+            off,
+            on_ms,
+            off_ms,
+            forever: repeats == 0,
+        };
+        let duration = on_ms.saturating_add(off_ms).saturating_mul(repeats as u32);
+        self.start(output, effect, duration, Easing::Linear)
+    }
+
+    /// Stop the transition of an output, leaving it at its current value
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    pub fn cancel(&mut self, output: u16) {
+        for slot in self.slots.iter_mut() {
+            if matches!(slot, Some(transition) if transition.output == output) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Whether every transition has completed
+    pub fn is_idle(&self) -> bool {
+        self.slots.iter().all(Option::is_none) && self.playback.is_none() && self.timeline.is_none()
+    }
+
+    /// Play back a pattern, replacing any pattern being played. Transitions of the same outputs
+    /// continue to run, and take precedence as they are written after the pattern
+    /// * `pattern` - the pattern, usually a `const`
+    pub fn play(&mut self, pattern: &'static Pattern<V>) {
+        self.playback = Some(Playback {
+            pattern,
+            frame: 0,
+            elapsed: 0,
+            written: false,
+        });
+    }
+
+    /// Play back a timeline, replacing any timeline being played. Patterns and transitions of the same
+    /// outputs continue to run, transitions take precedence as they are written after the timeline
+    /// * `timeline` - the timeline, usually a `const`
+    pub fn play_timeline(&mut self, timeline: &'static Timeline<V>) {
+        self.timeline = Some(TimelinePlayback {
+            timeline,
+            elapsed: 0,
+            written: false,
+        });
+    }
+
+    /// Stop playing back the pattern and the timeline, leaving the outputs at their current values
+    pub fn stop(&mut self) {
+        self.playback = None;
+        self.timeline = None;
+    }
+
+    animator_methods!(blocking);
+
+    /// Enqueue a transition, replacing any transition of the same output
+    fn start(
+        &mut self,
+        output: u16,
+        effect: Effect<V>,
+        duration_ms: u32,
+        easing: Easing,
+    ) -> Result<(), Error> {
+        self.cancel(output);
+        let slot = self
+            .slots
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Error::NoFreeSlot)?;
+        *slot = Some(Transition {
+            output,
+            effect,
+            duration: duration_ms,
+            elapsed: 0,
+            last: None,
+            easing,
+        });
+        Ok(())
+    }
+}
+
+impl<const N: usize> Animator<(u8, [u8; 3]), N> {
+    /// Sweep the hue of a channel at full saturation
+    /// * `channel` - the channel number
+    /// * `brightness` - the channel brightness
+    /// * `from` - the starting hue in degrees
+    /// * `to` - the final hue in degrees, may be lower than `from` to sweep backwards
+    /// * `duration_ms` - Duration of the sweep in milliseconds
+    pub fn sweep(
+        &mut self,
+        channel: u8,
+        brightness: u8,
+        from: u16,
+        to: u16,
+        duration_ms: u32,
+    ) -> Result<(), Error> {
+        let effect = Effect::Sweep {
+            brightness,
+            from,
+            to,
+            map: |brightness, hue| (brightness, hsv_to_rgb(hue, 255, 255)),
+        };
+        self.start(channel as u16, effect, duration_ms, Easing::Linear)
+    }
+}
+
+/// Direction of travel of a chase
+#[derive(Clone, Copy)]
+pub enum Direction {
+    /// Towards higher LED indices
+    Forward,
+    /// Towards lower LED indices
+    Reverse,
+}
+
+/// Shifts a short pattern across every LED of the chain in monochromatic mode, wrapping around at the end.
+/// The first value of the pattern is the head and the following values trail behind it
+///
+/// ```ignore
+/// let mut chase = Chase::new(&[0xFF, 0x40, 0x10], Direction::Forward, 50);
+/// // Every 10ms
+/// chase.tick(&mut controller, 10)?;
+/// ```
+pub struct Chase<'a> {
+    pattern: &'a [u8],
+    direction: Direction,
+    /// Time the head spends on each LED in milliseconds
+    step_ms: u32,
+    /// Time elapsed since the last step in milliseconds
+    elapsed: u32,
+    /// Offset of the head from the first LED
+    position: u16,
+    /// Whether the current position has been written
+    written: bool,
+}
+
+impl<'a> Chase<'a> {
+    /// Create a chase starting from the first LED
+    /// * `pattern` - the head and tail luminosity values
+    /// * `direction` - direction of travel
+    /// * `step_ms` - Time the head spends on each LED in milliseconds, lower is faster
+    pub fn new(pattern: &'a [u8], direction: Direction, step_ms: u32) -> Self {
+        Self {
+            pattern,
+            direction,
+            step_ms: step_ms.max(1),
+            elapsed: 0,
+            position: 0,
+            written: false,
+        }
+    }
+
+    /// Set the direction of travel
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    /// Set the time the head spends on each LED in milliseconds, lower is faster
+    pub fn set_step(&mut self, step_ms: u32) {
+        self.step_ms = step_ms.max(1);
+    }
+
+    /// Advance the chase and write the chain whenever the head moves
+    /// * `driver` - the LP50xx driving the chain
+    /// * `elapsed_ms` - Time elapsed since the previous tick in milliseconds
+    pub fn tick<IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<MonochromaticMode, IFACE, EN, Enabled>,
+        elapsed_ms: u32,
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        let leds = driver.led_count();
+        if leds == 0 {
+            return Err(Error::LedOutOfRange);
+        }
+        self.elapsed = self.elapsed.saturating_add(elapsed_ms);
+        let steps = (self.elapsed / self.step_ms % leds as u32) as u16;
+        self.elapsed %= self.step_ms;
+
+        if steps > 0 {
+            self.position = match self.direction {
+                Direction::Forward => (self.position + steps) % leds,
+                Direction::Reverse => (self.position + leds - steps) % leds,
+            };
+            self.written = false;
+        }
+        if self.written {
+            return Ok(());
+        }
+
+        let (pattern, position, direction) = (self.pattern, self.position, self.direction);
+        driver.write_leds(|led| {
+            // Distance behind the head in the direction of travel
+            let distance = match direction {
+                Direction::Forward => (position + leds - (led - 1)) % leds,
+                Direction::Reverse => (led - 1 + leds - position) % leds,
+            };
+            pattern.get(distance as usize).copied().unwrap_or(0)
+        })?;
+        self.written = true;
+        Ok(())
+    }
+}
+
+/// The asynchronous animator, generated from the same templates as the blocking animator
+#[cfg(feature = "async")]
+mod asynch {
+    use super::{AnimateAsync as Animate, Animator, Blend, Playback, TimelinePlayback};
+    use crate::Error;
+
+    impl<V: Blend> Playback<V> {
+        playback_methods!(asynch);
+    }
+
+    impl<V: Blend> TimelinePlayback<V> {
+        timeline_methods!(asynch);
+    }
+
+    impl<V: Blend, const N: usize> Animator<V, N> {
+        animator_methods!(asynch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animate, Animator, Easing, Frame, Keyframe, Pattern, Timeline};
+    use crate::Error;
+
+    /// Records the number of writes and the last value of output 1
+    #[derive(Default)]
+    struct Outputs {
+        writes: usize,
+        value: u8,
+    }
+
+    impl Animate for Outputs {
+        type Value = u8;
+
+        fn apply(&mut self, _output: u16, value: u8) -> Result<(), Error> {
+            self.writes += 1;
+            self.value = value;
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl super::AnimateAsync for Outputs {
+        type Value = u8;
+
+        async fn apply(&mut self, output: u16, value: u8) -> Result<(), Error> {
+            Animate::apply(self, output, value)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_tick_writes_like_blocking_tick() {
+        use core::future::Future;
+
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 2> = Animator::new();
+        animator.fade(1, 0, 255, 100).unwrap();
+
+        let waker = crate::asynch::tests::noop_waker();
+        let mut context = core::task::Context::from_waker(&waker);
+        {
+            let tick = core::pin::pin!(animator.tick_async(&mut outputs, 50));
+            assert!(tick.poll(&mut context).is_ready());
+        }
+        assert_eq!((outputs.writes, outputs.value), (1, 127));
+    }
+
+    #[test]
+    fn tick_only_writes_changed_values() {
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 2> = Animator::new();
+        animator.fade(1, 0, 255, 100).unwrap();
+
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!(outputs.value, 127);
+        animator.tick(&mut outputs, 0).unwrap();
+        assert_eq!(outputs.writes, 1);
+
+        animator.tick(&mut outputs, 100).unwrap();
+        assert_eq!((outputs.writes, outputs.value), (2, 255));
+        assert!(animator.is_idle());
+    }
+
+    #[test]
+    fn blink_ends_off() {
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 2> = Animator::new();
+        animator.blink(1, 0xFF, 0, 100, 100, 2).unwrap();
+
+        animator.tick(&mut outputs, 0).unwrap();
+        assert_eq!(outputs.value, 0xFF);
+        animator.tick(&mut outputs, 150).unwrap();
+        assert_eq!(outputs.value, 0);
+        animator.tick(&mut outputs, 100).unwrap();
+        assert_eq!(outputs.value, 0xFF);
+        animator.tick(&mut outputs, 150).unwrap();
+        assert_eq!((outputs.writes, outputs.value), (4, 0));
+        assert!(animator.is_idle());
+    }
+
+    #[test]
+    fn long_blink_periods_saturate() {
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 2> = Animator::new();
+        animator.blink(1, 0xFF, 0, u32::MAX, u32::MAX, 0).unwrap();
+
+        animator.tick(&mut outputs, u32::MAX - 1).unwrap();
+        assert_eq!(outputs.value, 0xFF);
+        animator.tick(&mut outputs, 10).unwrap();
+        assert_eq!(outputs.value, 0xFF);
+    }
+
+    #[test]
+    fn pattern_plays_every_frame() {
+        const PATTERN: Pattern<u8> = Pattern {
+            frames: &[
+                Frame {
+                    outputs: &[(1, 0xFF)],
+                    duration_ms: 100,
+                },
+                Frame {
+                    outputs: &[(1, 0x40)],
+                    duration_ms: 100,
+                },
+            ],
+            repeat: false,
+        };
+
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 1> = Animator::new();
+        animator.play(&PATTERN);
+
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!(outputs.value, 0xFF);
+        animator.tick(&mut outputs, 100).unwrap();
+        assert_eq!((outputs.writes, outputs.value), (2, 0x40));
+        assert!(!animator.is_idle());
+        animator.tick(&mut outputs, 50).unwrap();
+        assert!(animator.is_idle());
+    }
+
+    #[test]
+    fn breathing_curve_peaks_mid_period() {
+        assert_eq!(super::breath(0, 1000), 0);
+        assert_eq!(super::breath(250, 1000), 126);
+        assert_eq!(super::breath(500, 1000), 255);
+        assert_eq!(super::breath(1000, 1000), 0);
+    }
+
+    #[test]
+    fn easing_curves_span_the_full_range() {
+        let curves = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Cubic,
+            Easing::Exponential,
+        ];
+        for easing in curves.iter().copied() {
+            assert_eq!((easing.apply(0), easing.apply(255)), (0, 255));
+            let mut last = 0;
+            for fraction in 0..=255 {
+                let eased = easing.apply(fraction);
+                assert!(eased >= last);
+                last = eased;
+            }
+        }
+        assert_eq!(Easing::EaseIn.apply(128), 64);
+        assert_eq!(Easing::EaseOut.apply(128), 192);
+    }
+
+    #[test]
+    fn fade_follows_easing() {
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 2> = Animator::new();
+        animator.fade_with(1, 0, 255, 100, Easing::EaseIn).unwrap();
+
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!(outputs.value, 63);
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!(outputs.value, 255);
+    }
+
+    #[test]
+    fn timeline_interpolates_between_keyframes() {
+        static TIMELINE: Timeline<u8> = Timeline {
+            keyframes: &[
+                Keyframe {
+                    time_ms: 0,
+                    outputs: &[(1, 0x00)],
+                    easing: Easing::Linear,
+                },
+                Keyframe {
+                    time_ms: 100,
+                    outputs: &[(1, 0xFF), (2, 0x10)],
+                    easing: Easing::Linear,
+                },
+                Keyframe {
+                    time_ms: 200,
+                    outputs: &[(2, 0x20)],
+                    easing: Easing::Linear,
+                },
+            ],
+            repeat: false,
+        };
+        assert_eq!(TIMELINE.value(1, 50), Some(127));
+        assert_eq!(TIMELINE.value(1, 150), Some(0xFF));
+        assert_eq!(TIMELINE.value(2, 50), None);
+        assert_eq!(TIMELINE.value(2, 150), Some(0x17));
+
+        let mut outputs = Outputs::default();
+        let mut animator: Animator<u8, 1> = Animator::new();
+        animator.play_timeline(&TIMELINE);
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!((outputs.writes, outputs.value), (1, 127));
+        animator.tick(&mut outputs, 50).unwrap();
+        assert_eq!(outputs.writes, 3);
+
+        animator.tick(&mut outputs, 150).unwrap();
+        assert_eq!((outputs.writes, outputs.value), (4, 0x20));
+        assert!(animator.is_idle());
+    }
+}
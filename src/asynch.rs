@@ -0,0 +1,181 @@
+//! Asynchronous I2C support via `embedded-hal-async`, enabled with the `async` feature
+
+use crate::framebuffer::MAX_REGISTERS;
+use crate::registers;
+use crate::{
+    get_channel_registers, Address, CallbackInterface, ChannelIndex, ColorMode, DeviceConfig,
+    Disabled, Enabled, Error, I2cInterface, LP50xx, LedIndex, MonochromaticMode,
+    MAX_REGISTER_COUNT,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+/// The asynchronous counterpart of `Lp50xxInterface`
+#[allow(async_fn_in_trait)]
+pub trait Lp50xxAsyncInterface {
+    /// Write the data payload to the LP50xx
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload to be sent, beginning with the register address
+    async fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error>;
+
+    /// Write the data payload to the LP50xx and then read back into the buffer.
+    /// By default reading is not supported by the transport
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload to be sent, typically the register address
+    /// * `buffer` - The buffer the read data is placed in
+    async fn write_read(
+        &mut self,
+        address: u8,
+        data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        let _ = (address, data, buffer);
+        Err(Error::ReadUnsupported)
+    }
+
+    /// Wait between the attempts of a write retried by the `RetryPolicy`, without blocking the executor.
+    /// By default the transport does not wait, a transport holding an asynchronous delay provider can
+    /// await it here
+    /// * `delay_us` - Time to wait in microseconds
+    async fn retry_delay(&mut self, delay_us: u32) {
+        let _ = delay_us;
+    }
+}
+
+impl<I2C> Lp50xxAsyncInterface for I2cInterface<I2C>
+where
+    I2C: I2c,
+{
+    async fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        self.i2c
+            .write(address, data)
+            .await
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.i2c
+            .write_read(address, data, buffer)
+            .await
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+}
+
+impl<CB> Lp50xxAsyncInterface for CallbackInterface<CB>
+where
+    CB: FnMut(u8, &[u8]),
+{
+    async fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        (self.callback)(address, data);
+        Ok(())
+    }
+}
+
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE>
+where
+    IFACE: Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    transport_methods!(asynch);
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Disabled>
+where
+    IFACE: Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    disabled_methods!(asynch);
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    enabled_methods!(asynch);
+}
+
+impl<IFACE, EN> LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    color_methods!(asynch);
+}
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxAsyncInterface,
+    EN: OutputPin,
+{
+    monochromatic_methods!(asynch);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::Lp50xxAsyncInterface;
+    use crate::{Enabled, Error, LP50xx, Model, NoEnable, RetryPolicy};
+    use core::future::Future;
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    /// Waker which does nothing, the futures under test complete on their first poll.
+    /// `Waker::noop` would require Rust 1.85
+    pub(crate) fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        // SAFETY: the vtable functions ignore the data pointer, so the null pointer is never dereferenced
+        unsafe { Waker::from_raw(clone(core::ptr::null())) }
+    }
+
+    /// Transport failing the first write
+    #[derive(Default)]
+    struct Flaky {
+        writes: usize,
+        /// Time waited between attempts in microseconds
+        waited: u32,
+    }
+
+    impl Lp50xxAsyncInterface for Flaky {
+        async fn write(&mut self, _address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.writes += 1;
+            if self.writes == 1 {
+                return Err(Error::CommError(embedded_hal::i2c::ErrorKind::Other));
+            }
+            Ok(())
+        }
+
+        async fn retry_delay(&mut self, delay_us: u32) {
+            self.waited += delay_us;
+        }
+    }
+
+    #[test]
+    fn async_writes_follow_the_retry_policy() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Flaky::default(), NoEnable);
+        let mut driver = driver.into_monochromatic_mode().into_state::<Enabled>();
+        driver.set_retry_policy(
+            RetryPolicy::new(2).with_delay(100, |_| panic!("blocking delay in the async driver")),
+        );
+
+        let waker = noop_waker();
+        let mut context = Context::from_waker(&waker);
+        {
+            let set = core::pin::pin!(driver.set_async(1, 0x80));
+            assert!(matches!(set.poll(&mut context), Poll::Ready(Ok(()))));
+        }
+        let stats = driver.stats();
+        assert_eq!((stats.retries, stats.recovered), (1, 1));
+        let (flaky, _) = driver.release();
+        assert_eq!(flaky.waited, 100);
+    }
+}
@@ -0,0 +1,184 @@
+//! Continuously numbered LED chains spanning several drivers
+//!
+//! A single `LP50xx` addresses at most four chips. `Chain` combines several monochromatic drivers,
+//! such as one per TCA9548A channel, into one chain with LED numbering continuing from one driver
+//! to the next.
+//!
+//! ```ignore
+//! let mut chain = Chain::new([first, second]);
+//! // The first LED of the second driver, with four LP5012 chips on the first
+//! chain.set(49, 0xFF)?;
+//! ```
+//!
+//! Drivers of different types, such as drivers on different I2C peripherals, are chained as a tuple.
+//! Tuples nest to chain more than two:
+//!
+//! ```ignore
+//! let mut chain = (i2c1_driver, (i2c2_driver, i2c3_driver));
+//! chain.fill(0x80)?;
+//! ```
+
+use crate::{Enabled, Error, LP50xx, Lp50xxInterface, MonochromaticMode};
+use embedded_hal::digital::OutputPin;
+
+/// A chain of monochromatic LEDs numbered from 1
+pub trait LedChain {
+    /// Number of LEDs in the chain
+    fn led_count(&self) -> u16;
+
+    /// Set the desired LED value
+    /// * `led` - the LED index beginning at 1
+    /// * `value` - luminosity value
+    fn set(&mut self, led: u16, value: u8) -> Result<(), Error>;
+
+    /// Set every LED of the chain to the same value
+    /// * `value` - luminosity value
+    fn fill(&mut self, value: u8) -> Result<(), Error>;
+}
+
+impl<IFACE, EN> LedChain for LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    // Inherent methods take precedence over the trait methods
+    fn led_count(&self) -> u16 {
+        self.led_count()
+    }
+
+    fn set(&mut self, led: u16, value: u8) -> Result<(), Error> {
+        self.set(led, value)
+    }
+
+    fn fill(&mut self, value: u8) -> Result<(), Error> {
+        self.fill(value)
+    }
+}
+
+/// Chain of `N` drivers of the same type, numbered in order
+pub struct Chain<D, const N: usize> {
+    drivers: [D; N],
+}
+
+impl<D, const N: usize> Chain<D, N>
+where
+    D: LedChain,
+{
+    /// Create the chain
+    /// * `drivers` - the drivers in the order of their LEDs
+    pub fn new(drivers: [D; N]) -> Self {
+        Self { drivers }
+    }
+
+    /// Release the drivers
+    pub fn release(self) -> [D; N] {
+        self.drivers
+    }
+}
+
+impl<D, const N: usize> LedChain for Chain<D, N>
+where
+    D: LedChain,
+{
+    fn led_count(&self) -> u16 {
+        self.drivers.iter().map(LedChain::led_count).sum()
+    }
+
+    fn set(&mut self, led: u16, value: u8) -> Result<(), Error> {
+        if led == 0 {
+            return Err(Error::InvalidLed);
+        }
+
+        let mut led = led;
+        for driver in self.drivers.iter_mut() {
+            let count = driver.led_count();
+            if led <= count {
+                return driver.set(led, value);
+            }
+            led -= count;
+        }
+        Err(Error::LedOutOfRange)
+    }
+
+    fn fill(&mut self, value: u8) -> Result<(), Error> {
+        self.drivers
+            .iter_mut()
+            .try_for_each(|driver| driver.fill(value))
+    }
+}
+
+impl<A, B> LedChain for (A, B)
+where
+    A: LedChain,
+    B: LedChain,
+{
+    fn led_count(&self) -> u16 {
+        self.0.led_count() + self.1.led_count()
+    }
+
+    fn set(&mut self, led: u16, value: u8) -> Result<(), Error> {
+        let count = self.0.led_count();
+        if led <= count {
+            self.0.set(led, value)
+        } else {
+            self.1.set(led - count, value)
+        }
+    }
+
+    fn fill(&mut self, value: u8) -> Result<(), Error> {
+        self.0.fill(value)?;
+        self.1.fill(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chain, LedChain};
+    use crate::Error;
+
+    /// Chain of 12 LEDs recording the last LED set
+    #[derive(Default)]
+    struct Leds {
+        last: Option<u16>,
+    }
+
+    impl LedChain for Leds {
+        fn led_count(&self) -> u16 {
+            12
+        }
+
+        fn set(&mut self, led: u16, _value: u8) -> Result<(), Error> {
+            self.last = Some(led);
+            Ok(())
+        }
+
+        fn fill(&mut self, _value: u8) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn numbering_continues_across_drivers() {
+        let mut chain = Chain::new([Leds::default(), Leds::default()]);
+        assert_eq!(chain.led_count(), 24);
+        chain.set(13, 0xFF).unwrap();
+        assert!(matches!(chain.set(25, 0xFF), Err(Error::LedOutOfRange)));
+
+        let [first, second] = chain.release();
+        assert_eq!((first.last, second.last), (None, Some(1)));
+    }
+
+    #[test]
+    fn tuples_chain_different_drivers() {
+        let mut chain = (
+            Leds::default(),
+            Chain::new([Leds::default(), Leds::default()]),
+        );
+        assert_eq!(chain.led_count(), 36);
+        chain.set(30, 0xFF).unwrap();
+        assert!(matches!(chain.set(37, 0xFF), Err(Error::LedOutOfRange)));
+
+        let [_, last] = chain.1.release();
+        assert_eq!(last.last, Some(6));
+    }
+}
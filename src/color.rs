@@ -0,0 +1,88 @@
+//! Color conversions suitable for no_std targets
+//!
+//! With the `rgb` feature enabled, `rgb::RGB8` converts from and into the `[r, g, b]` arrays used throughout
+//! this crate, and can be passed directly to the color mode setters.
+
+#[cfg(feature = "rgb")]
+pub use rgb::RGB8;
+
+/// Convert a HSV color into RGB values using integer math only
+/// * `hue` - Hue in degrees, values of 360 and above wrap around
+/// * `saturation` - Saturation, 0 being grey and 255 being fully saturated
+/// * `value` - Value, 0 being black and 255 being full intensity
+pub fn hsv_to_rgb(hue: u16, saturation: u8, value: u8) -> [u8; 3] {
+    let hue = hue % 360;
+    let (s, v) = (saturation as u32, value as u32);
+    let remainder = (hue as u32 % 60) * 255 / 60;
+
+    let p = (v * (255 - s) / 255) as u8;
+    let q = (v * (255 - s * remainder / 255) / 255) as u8;
+    let t = (v * (255 - s * (255 - remainder) / 255) / 255) as u8;
+
+    match hue / 60 {
+        0 => [value, t, p],
+        1 => [q, value, p],
+        2 => [p, value, t],
+        3 => [p, q, value],
+        4 => [t, p, value],
+        _ => [value, p, q],
+    }
+}
+
+/// Order in which the red, green and blue LEDs are wired to the OUTx pins of each RGB channel
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorOrder {
+    #[default]
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Reorder RGB values into the order of the OUTx pins
+    /// * `color` - the RGB values
+    pub fn apply<T>(self, [r, g, b]: [T; 3]) -> [T; 3] {
+        match self {
+            ColorOrder::Rgb => [r, g, b],
+            ColorOrder::Rbg => [r, b, g],
+            ColorOrder::Grb => [g, r, b],
+            ColorOrder::Gbr => [g, b, r],
+            ColorOrder::Brg => [b, r, g],
+            ColorOrder::Bgr => [b, g, r],
+        }
+    }
+}
+
+/// Convert a HSV color into an `RGB8` using integer math only, see `hsv_to_rgb`
+#[cfg(feature = "rgb")]
+pub fn hsv_to_rgb8(hue: u16, saturation: u8, value: u8) -> RGB8 {
+    hsv_to_rgb(hue, saturation, value).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hsv_to_rgb, ColorOrder};
+
+    #[test]
+    fn color_order_maps_rgb_to_pins() {
+        assert_eq!(ColorOrder::Rgb.apply([1, 2, 3]), [1, 2, 3]);
+        assert_eq!(ColorOrder::Grb.apply([1, 2, 3]), [2, 1, 3]);
+        assert_eq!(ColorOrder::Bgr.apply([1, 2, 3]), [3, 2, 1]);
+    }
+
+    #[test]
+    fn correct_hsv_conversion() {
+        assert_eq!(hsv_to_rgb(0, 255, 255), [255, 0, 0]);
+        assert_eq!(hsv_to_rgb(120, 255, 255), [0, 255, 0]);
+        assert_eq!(hsv_to_rgb(240, 255, 255), [0, 0, 255]);
+        assert_eq!(hsv_to_rgb(60, 255, 255), [255, 255, 0]);
+        assert_eq!(hsv_to_rgb(360, 255, 255), [255, 0, 0]);
+        assert_eq!(hsv_to_rgb(200, 0, 128), [128, 128, 128]);
+        assert_eq!(hsv_to_rgb(90, 255, 0), [0, 0, 0]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Typed configuration of the DEVICE_CONFIG1 and LED_CONFIG registers
+
+/// Configuration of the LP50xx, encoded into the DEVICE_CONFIG1 register.
+/// The default matches the power-on default of the LP50xx.
+/// For information regarding each of these settings, please consult the datasheet.
+/// With the `serde` feature it is serialized as the DEVICE_CONFIG1 register value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "u8", into = "u8")
+)]
+pub struct DeviceConfig {
+    log_scale: bool,
+    power_save: bool,
+    auto_incr: bool,
+    pwm_dithering: bool,
+    max_current_option: bool,
+    global_off: bool,
+}
+
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        Self {
+            log_scale: true,
+            power_save: true,
+            auto_incr: true,
+            pwm_dithering: true,
+            max_current_option: false,
+            global_off: false,
+        }
+    }
+}
+
+impl DeviceConfig {
+    /// Logarithmic scale dimming curve
+    pub fn log_scale(mut self, enable: bool) -> Self {
+        self.log_scale = enable;
+        self
+    }
+
+    /// Automatic power-saving mode enabled
+    pub fn power_save(mut self, enable: bool) -> Self {
+        self.power_save = enable;
+        self
+    }
+
+    /// The auto-increment feature allows writing or reading several consecutive registers within one transmission.
+    pub fn auto_incr(mut self, enable: bool) -> Self {
+        self.auto_incr = enable;
+        self
+    }
+
+    /// PWM dithering mode enabled
+    pub fn pwm_dithering(mut self, enable: bool) -> Self {
+        self.pwm_dithering = enable;
+        self
+    }
+
+    /// Output maximum current enable: IMAX = 35 mA, disable: IMAX = 25.5mA
+    pub fn max_current_option(mut self, enable: bool) -> Self {
+        self.max_current_option = enable;
+        self
+    }
+
+    /// Shut down all LEDs when enabled
+    pub fn global_off(mut self, enable: bool) -> Self {
+        self.global_off = enable;
+        self
+    }
+
+    /// Logarithmic scale dimming curve enabled
+    pub fn is_log_scale(&self) -> bool {
+        self.log_scale
+    }
+
+    /// Automatic power-saving mode enabled
+    pub fn is_power_save(&self) -> bool {
+        self.power_save
+    }
+
+    /// Auto-increment enabled
+    pub fn is_auto_incr(&self) -> bool {
+        self.auto_incr
+    }
+
+    /// PWM dithering mode enabled
+    pub fn is_pwm_dithering(&self) -> bool {
+        self.pwm_dithering
+    }
+
+    /// Output maximum current of 35 mA enabled
+    pub fn is_max_current_option(&self) -> bool {
+        self.max_current_option
+    }
+
+    /// All LEDs shut down
+    pub fn is_global_off(&self) -> bool {
+        self.global_off
+    }
+
+    /// Decode a DEVICE_CONFIG1 register value, for example one read with `read_device_config`
+    pub fn from_u8(value: u8) -> Self {
+        Self {
+            log_scale: value & 1 << 5 != 0,
+            power_save: value & 1 << 4 != 0,
+            auto_incr: value & 1 << 3 != 0,
+            pwm_dithering: value & 1 << 2 != 0,
+            max_current_option: value & 1 << 1 != 0,
+            global_off: value & 1 != 0,
+        }
+    }
+
+    /// Encode the DEVICE_CONFIG1 register value
+    pub fn into_u8(self) -> u8 {
+        (self.log_scale as u8) << 5
+            | (self.power_save as u8) << 4
+            | (self.auto_incr as u8) << 3
+            | (self.pwm_dithering as u8) << 2
+            | (self.max_current_option as u8) << 1
+            | self.global_off as u8
+    }
+}
+
+impl From<u8> for DeviceConfig {
+    fn from(value: u8) -> Self {
+        Self::from_u8(value)
+    }
+}
+
+impl From<DeviceConfig> for u8 {
+    fn from(config: DeviceConfig) -> Self {
+        config.into_u8()
+    }
+}
+
+/// Assignment of the RGB channels to the bank, encoded into the LED_CONFIG0 register and, on the LP5030
+/// and LP5036, the LED_CONFIG1 register. The default assigns no channel to the bank.
+/// With the `serde` feature it is serialized as the bit mask of `into_u16`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "u16", into = "u16")
+)]
+pub struct LedConfig {
+    mask: u16,
+}
+
+impl LedConfig {
+    /// Assign a channel to the bank or give it back its own registers
+    /// * `channel` - the channel number beginning at 1, channels above 16 are ignored
+    /// * `enable` - Channel follows the bank registers
+    pub fn bank(mut self, channel: u8, enable: bool) -> Self {
+        let bit = channel
+            .checked_sub(1)
+            .and_then(|bit| 1u16.checked_shl(bit as u32))
+            .unwrap_or(0);
+        if enable {
+            self.mask |= bit;
+        } else {
+            self.mask &= !bit;
+        }
+        self
+    }
+
+    /// Whether a channel follows the bank registers
+    /// * `channel` - the channel number beginning at 1
+    pub fn in_bank(&self, channel: u8) -> bool {
+        self.bank(channel, false) != *self
+    }
+
+    /// Decode the LED_CONFIG0 and LED_CONFIG1 register values, LED_CONFIG1 being 0 on models without it
+    pub fn from_registers(led_config0: u8, led_config1: u8) -> Self {
+        Self::from_u16(u16::from_le_bytes([led_config0, led_config1]))
+    }
+
+    /// Encode the LED_CONFIG0 and LED_CONFIG1 register values
+    pub fn into_registers(self) -> [u8; 2] {
+        self.mask.to_le_bytes()
+    }
+
+    /// Decode a bit mask of the channels in the bank, bit 0 being channel 1
+    pub fn from_u16(mask: u16) -> Self {
+        Self { mask }
+    }
+
+    /// Encode a bit mask of the channels in the bank, bit 0 being channel 1
+    pub fn into_u16(self) -> u16 {
+        self.mask
+    }
+}
+
+impl From<u16> for LedConfig {
+    fn from(mask: u16) -> Self {
+        Self::from_u16(mask)
+    }
+}
+
+impl From<LedConfig> for u16 {
+    fn from(config: LedConfig) -> Self {
+        config.into_u16()
+    }
+}
+
+/// Delays of the enable and reset sequences in microseconds. The default follows the datasheet, boards with
+/// external RC filtering on EN may need longer delays and boards with strict boot time budgets shorter ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timing {
+    /// Time EN is held low by `enable` before it is raised
+    pub enable_low_us: u32,
+    /// Time EN is held low by `reset`, shutting the LP50xx down
+    pub shutdown_us: u32,
+    /// Time after EN is raised before the first transaction
+    pub startup_us: u32,
+    /// Time after the software reset before the LP50xx is accessed again
+    pub reset_us: u32,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            enable_low_us: 1_000,
+            shutdown_us: 10_000,
+            startup_us: 10_000,
+            reset_us: 1_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeviceConfig, LedConfig};
+
+    #[test]
+    fn correct_device_config_encoding() {
+        assert_eq!(DeviceConfig::default().into_u8(), 0x3C);
+        let config = DeviceConfig::default()
+            .log_scale(false)
+            .max_current_option(true)
+            .global_off(true);
+        assert_eq!(config.into_u8(), 0x1F);
+    }
+
+    #[test]
+    fn register_values_round_trip() {
+        let config = DeviceConfig::from_u8(0x1F);
+        assert!(!config.is_log_scale() && config.is_global_off());
+        assert_eq!(u8::from(config), 0x1F);
+
+        let banks = LedConfig::default()
+            .bank(1, true)
+            .bank(10, true)
+            .bank(0, true);
+        assert!(banks.in_bank(10) && !banks.in_bank(2));
+        assert_eq!(banks.into_registers(), [0x01, 0x02]);
+        assert_eq!(LedConfig::from_registers(0x01, 0x02), banks.bank(17, true));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_from_register_values() {
+        use serde::de::value::{Error, U16Deserializer, U8Deserializer};
+        use serde::Deserialize;
+
+        let config = DeviceConfig::deserialize(U8Deserializer::<Error>::new(0x1F)).unwrap();
+        assert_eq!(config, DeviceConfig::from_u8(0x1F));
+        let banks = LedConfig::deserialize(U16Deserializer::<Error>::new(0x0201)).unwrap();
+        assert!(banks.in_bank(1) && banks.in_bank(10));
+    }
+}
@@ -0,0 +1,199 @@
+//! Temporal dithering of 16 bit color and luminosity values
+//!
+//! The OUTx_COLOR registers are 8 bit, so slow fades at low brightness visibly step. A `Dither` holds
+//! 16 bit targets and, when ticked from a fast refresh timer, alternates each register between the two
+//! nearest 8 bit values so the average output has the full resolution.
+//!
+//! ```ignore
+//! let mut dither: Dither<8> = Dither::new();
+//! dither.set_led(&mut controller, 1, 0x0180)?;
+//! // From a refresh timer, for example at 1kHz
+//! dither.tick(&mut controller)?;
+//! ```
+
+use crate::{
+    get_channel_registers, Address, ColorMode, Enabled, Error, LP50xx, Lp50xxInterface,
+    MonochromaticMode,
+};
+use embedded_hal::digital::OutputPin;
+
+/// A dithered color register
+#[derive(Clone, Copy)]
+struct Output {
+    address: Address,
+    register: u8,
+    /// The register value in 8.8 fixed point
+    target: u16,
+    /// Accumulated fraction not yet written
+    error: u8,
+    /// The value last written to the register
+    last: Option<u8>,
+}
+
+/// Dithers up to `N` color registers
+pub struct Dither<const N: usize> {
+    outputs: [Option<Output>; N],
+}
+
+impl<const N: usize> Default for Dither<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Dither<N> {
+    pub fn new() -> Self {
+        Self { outputs: [None; N] }
+    }
+
+    /// Stop dithering every register, leaving them at their current values
+    pub fn clear(&mut self) {
+        self.outputs = [None; N];
+    }
+
+    /// Set an LED to a 16 bit luminosity value in monochromatic mode
+    /// * `driver` - the LP50xx driving the LED
+    /// * `led` - the LED index beginning at 1
+    /// * `value` - luminosity value
+    pub fn set_led<IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<MonochromaticMode, IFACE, EN, Enabled>,
+        led: u16,
+        value: u16,
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        let (address, register) = driver.get_led_output(led)?;
+        let target = driver.scale_wide(driver.correct_wide(value));
+        self.insert(address, register, target)
+    }
+
+    /// Set a channel to a brightness and 16 bit RGB values in color mode. The brightness register is
+    /// written immediately and the color registers are dithered
+    /// * `driver` - the LP50xx driving the channel
+    /// * `channel` - the channel index beginning at 1
+    /// * `brightness` - the channel brightness
+    /// * `color` - the channel RGB values
+    pub fn set_channel<IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<ColorMode, IFACE, EN, Enabled>,
+        channel: u8,
+        brightness: u8,
+        color: [u16; 3],
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        let (bright_addr, color_addr) = get_channel_registers(channel, driver.model)?;
+        let address = driver.active_address;
+
+        // Ensure every color register fits before changing any output
+        let free = self
+            .outputs
+            .iter()
+            .filter(|output| output.is_none())
+            .count();
+        let replaced = (color_addr..color_addr + 3)
+            .filter(|register| self.find(address, *register).is_some())
+            .count();
+        if free + replaced < 3 {
+            return Err(Error::NoFreeSlot);
+        }
+
+        driver.write_outputs(address, bright_addr, &[driver.scale(brightness)])?;
+        for (register, target) in (color_addr..).zip(driver.correct_color_wide(channel, color)) {
+            self.insert(address, register, target)?;
+        }
+        Ok(())
+    }
+
+    /// Write the next dithered value of every register whose value changes
+    /// * `driver` - the LP50xx driving the outputs
+    pub fn tick<MODE, IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<MODE, IFACE, EN, Enabled>,
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        for output in self.outputs.iter_mut().flatten() {
+            let (error, carry) = output.error.overflowing_add(output.target as u8);
+            output.error = error;
+            let value = ((output.target >> 8) as u8).saturating_add(carry as u8);
+
+            if output.last != Some(value) {
+                driver.write_outputs(output.address, output.register, &[value])?;
+                output.last = Some(value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the slot of a register
+    fn find(&self, address: Address, register: u8) -> Option<usize> {
+        self.outputs.iter().position(|output| {
+            matches!(output, Some(output) if output.address == address && output.register == register)
+        })
+    }
+
+    /// Set the target of a register, replacing its previous target
+    fn insert(&mut self, address: Address, register: u8, target: u16) -> Result<(), Error> {
+        let index = self
+            .find(address, register)
+            .or_else(|| self.outputs.iter().position(Option::is_none))
+            .ok_or(Error::NoFreeSlot)?;
+
+        let last = self.outputs[index].and_then(|output| output.last);
+        self.outputs[index] = Some(Output {
+            address,
+            register,
+            target,
+            error: 0,
+            last,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dither;
+    use crate::{Address, Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport recording the value of every single register write
+    #[derive(Default)]
+    struct Recorder {
+        values: [u8; 4],
+        len: usize,
+    }
+
+    impl Lp50xxInterface for Recorder {
+        fn write(&mut self, _address: u8, data: &[u8]) -> Result<(), Error> {
+            self.values[self.len] = data[1];
+            self.len += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn output_alternates_between_nearest_values() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Recorder::default(), NoEnable);
+        let mut driver = driver
+            .into_monochromatic_mode()
+            .into_state::<crate::Enabled>();
+
+        let mut dither: Dither<1> = Dither::new();
+        dither.set_led(&mut driver, 1, 0x0180).unwrap();
+        for _ in 0..4 {
+            dither.tick(&mut driver).unwrap();
+        }
+        assert!(dither.find(Address::Independent(0), 0x0B).is_some());
+
+        let (recorder, _) = driver.release();
+        assert_eq!(&recorder.values[..recorder.len], &[1, 2, 1, 2]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Lighting effects built on top of the output setters
+
+use crate::animation::{breath, Blend};
+use crate::framebuffer::MAX_REGISTERS;
+use crate::{
+    get_channel_registers, Address, ChannelIndex, ColorMode, Enabled, Error, LP50xx, LedIndex,
+    Lp50xxInterface, MonochromaticMode,
+};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Ramp runs of consecutive registers from their current values to the target values in `fade_steps` steps,
+    /// the runs are stepped together so every output arrives at the same time
+    /// * `addr` - Address of the LP50xx
+    /// * `runs` - The first register and target register values of each run
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    /// * `delay` - Delay provider
+    fn fade_outputs<DELAY: DelayNs>(
+        &mut self,
+        addr: Address,
+        runs: &[(u8, &[u8])],
+        duration_ms: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let steps = self.fade_steps;
+        let step_us = duration_ms.saturating_mul(1000) / steps as u32;
+        let base = self.model.get_brightness_base();
+
+        for remaining in (1..=steps).rev() {
+            for (register, targets) in runs {
+                let current = self
+                    .frame
+                    .get(addr, (register - base) as usize, targets.len())?;
+
+                // Move a fraction of the remaining distance, so the last step always lands on the target
+                let mut values = [0u8; MAX_REGISTERS];
+                for ((value, current), target) in values.iter_mut().zip(current).zip(*targets) {
+                    let distance = *target as i32 - *current as i32;
+                    *value = (*current as i32 + distance / remaining as i32) as u8;
+                }
+                self.write_outputs(addr, *register, &values[..targets.len()])?;
+            }
+
+            if self.buffered {
+                self.flush()?;
+            }
+            delay.delay_us(step_us);
+        }
+        Ok(())
+    }
+
+    /// Breathe a single register between two values, stepping `fade_steps` times per breath
+    /// * `addr` - Address of the LP50xx
+    /// * `register` - Address of the register
+    /// * `(min, max)` - Values at the start and in the middle of each breath
+    /// * `correct` - Apply gamma correction to the values
+    /// * `period_ms` - Duration of a single breath in milliseconds
+    /// * `cycles` - Number of breaths
+    /// * `delay` - Delay provider
+    #[allow(clippy::too_many_arguments)]
+    fn breathe_output<DELAY: DelayNs>(
+        &mut self,
+        addr: Address,
+        register: u8,
+        (min, max): (u8, u8),
+        correct: bool,
+        period_ms: u32,
+        cycles: u16,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let steps = self.fade_steps as u32;
+        let step_us = period_ms.saturating_mul(1000) / steps;
+
+        for _ in 0..cycles {
+            for step in 0..steps {
+                let mut value = u8::blend(min, max, breath(step, steps));
+                if correct {
+                    value = self.correct(value);
+                }
+                self.write_outputs(addr, register, &[self.scale(value)])?;
+
+                if self.buffered {
+                    self.flush()?;
+                }
+                delay.delay_us(step_us);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<IFACE, EN> LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Fade a channel from its current brightness and RGB values to the target, blocking until complete
+    /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+    /// * `target` - the target brightness and RGB values
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    /// * `delay` - Delay provider
+    pub fn fade_to<DELAY: DelayNs>(
+        &mut self,
+        channel: impl ChannelIndex,
+        (brightness, [r, g, b]): (u8, [u8; 3]),
+        duration_ms: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let channel = channel.channel_index();
+        let (bright_addr, color_addr) = get_channel_registers(channel, self.model)?;
+        let brightness = [self.scale(brightness)];
+        let color = self.correct_color(channel, [r, g, b]);
+        self.fade_outputs(
+            self.active_address,
+            &[(bright_addr, &brightness), (color_addr, &color)],
+            duration_ms,
+            delay,
+        )
+    }
+
+    /// Breathe the brightness of a channel between two values, keeping its RGB values, blocking until complete.
+    /// Use `animation::Animator::breathe` for a non-blocking breathing effect
+    /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+    /// * `period_ms` - Duration of a single breath in milliseconds
+    /// * `min` - the brightness at the start and end of each breath
+    /// * `max` - the brightness in the middle of each breath
+    /// * `cycles` - Number of breaths
+    /// * `delay` - Delay provider
+    pub fn breathe<DELAY: DelayNs>(
+        &mut self,
+        channel: impl ChannelIndex,
+        period_ms: u32,
+        min: u8,
+        max: u8,
+        cycles: u16,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let (bright_addr, _) = get_channel_registers(channel.channel_index(), self.model)?;
+        self.breathe_output(
+            self.active_address,
+            bright_addr,
+            (min, max),
+            false,
+            period_ms,
+            cycles,
+            delay,
+        )
+    }
+}
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Fade an LED from its current luminosity to the target, blocking until complete
+    /// * `led` - the LED index beginning at 1
+    /// * `target` - the target luminosity value
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    /// * `delay` - Delay provider
+    pub fn fade_to<DELAY: DelayNs>(
+        &mut self,
+        led: impl LedIndex,
+        target: u8,
+        duration_ms: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let (address, register, value) = self.get_led_write(led.led_index(), target)?;
+        self.fade_outputs(address, &[(register, &[value])], duration_ms, delay)
+    }
+
+    /// Breathe an LED between two luminosity values, blocking until complete.
+    /// Use `animation::Animator::breathe` for a non-blocking breathing effect
+    /// * `led` - the LED index beginning at 1
+    /// * `period_ms` - Duration of a single breath in milliseconds
+    /// * `min` - the luminosity at the start and end of each breath
+    /// * `max` - the luminosity in the middle of each breath
+    /// * `cycles` - Number of breaths
+    /// * `delay` - Delay provider
+    pub fn breathe<DELAY: DelayNs>(
+        &mut self,
+        led: impl LedIndex,
+        period_ms: u32,
+        min: u8,
+        max: u8,
+        cycles: u16,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let (address, register) = self.get_led_output(led.led_index())?;
+        self.breathe_output(
+            address,
+            register,
+            (min, max),
+            true,
+            period_ms,
+            cycles,
+            delay,
+        )
+    }
+
+    /// Light the first LEDs of the chain in proportion to a level, for battery or level displays on
+    /// monochromatic bars. With continuous addressing the bar spans every LED of the chain
+    /// * `percent` - the level from 0 to 100
+    /// * `value` - luminosity value of the lit LEDs
+    /// * `fractional` - light the LED at the end of the bar proportionally to the remainder of the level
+    pub fn set_bar_graph(&mut self, percent: u8, value: u8, fractional: bool) -> Result<(), Error> {
+        // The level in 1/255ths of an LED
+        let level = percent.min(100) as u32 * self.led_count() as u32 * 255 / 100;
+        let (lit, remainder) = ((level / 255) as u16, level % 255);
+
+        self.write_leds(|led| {
+            if led <= lit {
+                value
+            } else if led == lit + 1 && fractional {
+                (value as u32 * remainder / 255) as u8
+            } else {
+                0
+            }
+        })
+    }
+}
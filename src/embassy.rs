@@ -0,0 +1,45 @@
+//! Embassy integration, enabled with the `embassy` feature
+//!
+//! The asynchronous driver works with the I2C peripheral of any embassy HAL in async mode, and
+//! `animate` plays an `Animator` back paced by `embassy_time::Timer`, so effects run from a single task:
+//!
+//! ```ignore
+//! #[embassy_executor::task]
+//! async fn leds(mut controller: AsyncLP50xx<ColorMode, I2c<'static, Async>, Output<'static>>) {
+//!     let mut animator: Animator<(u8, [u8; 3]), 4> = Animator::new();
+//!     animator.sweep(1, 0xFF, 0, 360, 5000).ok();
+//!     lp50xx::embassy::animate(&mut controller, &mut animator, Duration::from_millis(10)).await.ok();
+//! }
+//! ```
+
+use crate::animation::{AnimateAsync, Animator, Blend};
+use crate::{Enabled, Error, I2cInterface, LP50xx};
+use embassy_time::{Duration, Instant, Timer};
+
+/// The enabled asynchronous LP50xx on an I2C peripheral
+pub type AsyncLP50xx<MODE, I2C, EN> = LP50xx<MODE, I2cInterface<I2C>, EN, Enabled>;
+
+/// Tick the animator once per frame until it is idle, with the elapsed time measured by `embassy_time`.
+/// Animations which repeat forever never return, unless writing to the LP50xx fails
+/// * `driver` - the LP50xx driving the outputs
+/// * `animator` - the animator played back
+/// * `frame` - Time between ticks
+pub async fn animate<D, V, const N: usize>(
+    driver: &mut D,
+    animator: &mut Animator<V, N>,
+    frame: Duration,
+) -> Result<(), Error>
+where
+    D: AnimateAsync<Value = V>,
+    V: Blend,
+{
+    let mut last = Instant::now();
+    while !animator.is_idle() {
+        let now = Instant::now();
+        let elapsed_ms = (now - last).as_millis().min(u32::MAX as u64) as u32;
+        animator.tick_async(driver, elapsed_ms).await?;
+        last = now;
+        Timer::at(now + frame).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Serialization of register writes into caller provided buffers, for DMA driven I2C peripherals
+//!
+//! Set the outputs in buffered mode and flush them into a `FrameEncoder` instead of the transport.
+//! Each frame is a complete I2C write, the address byte followed by the register and values, ready
+//! to be handed to a DMA engine:
+//!
+//! ```ignore
+//! let mut buffer = [0u8; 128];
+//! let mut encoder = FrameEncoder::new(&mut buffer);
+//! controller.set_buffered(true);
+//! controller.set(1, 0xFF)?;
+//! loop {
+//!     let (_, more) = controller.flush_into(&mut encoder)?;
+//!     for frame in encoder.frames() {
+//!         dma_i2c_write(frame);
+//!     }
+//!     encoder.clear();
+//!     if !more {
+//!         break;
+//!     }
+//! }
+//! ```
+//!
+//! With the `embedded-dma` feature a `DmaFrame` in a static buffer is handed to the DMA transfer API
+//! of the HAL, one frame per transfer:
+//!
+//! ```ignore
+//! let mut frame = DmaFrame::new(singleton!(: [u8; 64] = [0; 64]).unwrap());
+//! while controller.flush_into_dma(&mut frame)? {
+//!     frame = i2c_dma.write(frame).wait();
+//!     frame.clear();
+//! }
+//! ```
+
+use crate::{Enabled, Error, LP50xx};
+
+/// Builder of I2C write frames in a caller provided buffer. Every frame is preceded by its length
+/// in the buffer, use `frames` to get the frames themselves
+pub struct FrameEncoder<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> FrameEncoder<'a> {
+    /// Create an empty encoder
+    /// * `buffer` - Buffer the frames are written into
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// Append a write frame, returns `Error::NoFreeSlot` when it does not fit in the remaining buffer
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload, beginning with the register address
+    pub fn push(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        let frame = 1 + data.len();
+        if frame > u8::MAX as usize || self.len + 1 + frame > self.buffer.len() {
+            return Err(Error::NoFreeSlot);
+        }
+
+        self.buffer[self.len] = frame as u8;
+        self.buffer[self.len + 1] = address << 1;
+        self.buffer[self.len + 2..self.len + 1 + frame].copy_from_slice(data);
+        self.len += 1 + frame;
+        Ok(())
+    }
+
+    /// The encoded frames, each beginning with the address byte with the write bit clear
+    pub fn frames(&self) -> Frames<'_> {
+        Frames {
+            buffer: &self.buffer[..self.len],
+        }
+    }
+
+    /// No frame has been encoded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every frame, for example once the DMA transfers are complete
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Release the underlying buffer
+    pub fn release(self) -> &'a mut [u8] {
+        self.buffer
+    }
+}
+
+/// Iterator over the frames of a `FrameEncoder`
+pub struct Frames<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, rest) = self.buffer.split_first()?;
+        let (frame, rest) = rest.split_at(*len as usize);
+        self.buffer = rest;
+        Some(frame)
+    }
+}
+
+/// A single write frame in a static buffer, which can be handed to DMA transfer APIs through the
+/// `embedded_dma::ReadBuffer` trait. Enabled with the `embedded-dma` feature
+#[cfg(feature = "embedded-dma")]
+pub struct DmaFrame {
+    buffer: &'static mut [u8],
+    len: usize,
+}
+
+#[cfg(feature = "embedded-dma")]
+impl DmaFrame {
+    /// Create an empty frame
+    /// * `buffer` - Buffer the frame is written into, at least 2 bytes plus the register values long
+    pub fn new(buffer: &'static mut [u8]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// Encode the write frame, returns `Error::NoFreeSlot` when the frame already holds a write or
+    /// the buffer is too small
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload, beginning with the register address
+    pub fn push(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        if self.len != 0 || 1 + data.len() > self.buffer.len() {
+            return Err(Error::NoFreeSlot);
+        }
+
+        self.buffer[0] = address << 1;
+        self.buffer[1..=data.len()].copy_from_slice(data);
+        self.len = 1 + data.len();
+        Ok(())
+    }
+
+    /// The encoded frame, beginning with the address byte with the write bit clear
+    pub fn frame(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// No write has been encoded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove the write, once the DMA transfer is complete
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Release the underlying buffer
+    pub fn release(self) -> &'static mut [u8] {
+        self.buffer
+    }
+}
+
+// SAFETY: the buffer is a `'static` borrow, so its address and length are stable when the frame is moved,
+// and it is only modified through `&mut self` methods
+#[cfg(feature = "embedded-dma")]
+unsafe impl embedded_dma::ReadBuffer for DmaFrame {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.buffer.as_ptr(), self.len)
+    }
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled> {
+    /// Encode all brightness and color registers changed since the last flush into frames, instead of
+    /// writing them to the transport. Each run of consecutive registers becomes a single frame.
+    /// Returns the number of bytes added to the encoder and whether registers remain pending because
+    /// they did not fit, flush again once the frames are transferred and the encoder is cleared
+    /// * `encoder` - The encoder receiving the frames
+    pub fn flush_into(&mut self, encoder: &mut FrameEncoder) -> Result<(usize, bool), Error> {
+        let start = encoder.len;
+        let more = loop {
+            match self.encode_pending(|address, data| encoder.push(address, data)) {
+                Ok(true) => {}
+                Ok(false) => break false,
+                Err(Error::NoFreeSlot) => break true,
+                Err(e) => return Err(e),
+            }
+        };
+        Ok((encoder.len - start, more))
+    }
+
+    /// Encode the next run of consecutive registers changed since the last flush into a DMA frame.
+    /// Returns false when no register is pending. Call it again once the transfer of the frame is complete
+    /// * `frame` - The empty frame receiving the write
+    #[cfg(feature = "embedded-dma")]
+    pub fn flush_into_dma(&mut self, frame: &mut DmaFrame) -> Result<bool, Error> {
+        self.encode_pending(|address, data| frame.push(address, data))
+    }
+
+    /// Encode the first run of pending registers, marking them as written once encoded.
+    /// Returns false when no register is pending
+    /// * `push` - Encodes the 7bit address and data payload of the write
+    fn encode_pending<F>(&mut self, mut push: F) -> Result<bool, Error>
+    where
+        F: FnMut(u8, &[u8]) -> Result<(), Error>,
+    {
+        let (addr, offset, values) = match self.frame.dirty_run() {
+            Some(run) => run,
+            None => return Ok(false),
+        };
+        let len = values.len();
+        let mut payload = [0u8; 1 + crate::MAX_REGISTERS];
+        payload[0] = self.model.get_brightness_base() + offset as u8;
+        payload[1..=len].copy_from_slice(values);
+
+        push(self.get_i2c_address(addr)?, &payload[..=len])?;
+        self.frame.clean(addr, offset, len);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameEncoder;
+    use crate::{CallbackInterface, LP50xx, Model, NoEnable};
+
+    #[test]
+    fn pending_writes_are_encoded() {
+        let interface = CallbackInterface::new(|_: u8, _: &[u8]| {});
+        let mut controller = LP50xx::init_with_interface(Model::LP5012, interface, NoEnable)
+            .into_monochromatic_mode()
+            .into_state::<crate::Enabled>();
+        controller.set_chain_length(2).unwrap();
+        controller.set_buffered(true);
+        controller.set(1, 0x10).unwrap();
+        controller.set(2, 0x20).unwrap();
+        controller.set(13, 0x30).unwrap();
+
+        let mut buffer = [0u8; 8];
+        let mut encoder = FrameEncoder::new(&mut buffer);
+        assert_eq!(controller.flush_into(&mut encoder).unwrap(), (5, true));
+        let mut frames = encoder.frames();
+        assert_eq!(frames.next(), Some(&[0x28, 0x0B, 0x10, 0x20][..]));
+        assert_eq!(frames.next(), None);
+
+        encoder.clear();
+        assert_eq!(controller.flush_into(&mut encoder).unwrap(), (4, false));
+        assert!(encoder.frames().eq([&[0x2A, 0x0B, 0x30][..]]));
+    }
+
+    #[cfg(feature = "embedded-dma")]
+    #[test]
+    fn pending_writes_are_encoded_into_dma_frames() {
+        extern crate std;
+        use super::DmaFrame;
+        use embedded_dma::ReadBuffer;
+
+        let interface = CallbackInterface::new(|_: u8, _: &[u8]| {});
+        let mut controller = LP50xx::init_with_interface(Model::LP5012, interface, NoEnable)
+            .into_monochromatic_mode()
+            .into_state::<crate::Enabled>();
+        controller.set_chain_length(2).unwrap();
+        controller.set_buffered(true);
+        controller.set(13, 0x30).unwrap();
+
+        let buffer: &'static mut [u8] = std::boxed::Box::leak(std::boxed::Box::new([0u8; 8]));
+        let mut frame = DmaFrame::new(buffer);
+        assert!(controller.flush_into_dma(&mut frame).unwrap());
+        let (pointer, len) = unsafe { frame.read_buffer() };
+        assert_eq!(
+            unsafe { core::slice::from_raw_parts(pointer, len) },
+            &[0x2A, 0x0B, 0x30]
+        );
+        frame.clear();
+        assert!(!controller.flush_into_dma(&mut frame).unwrap());
+    }
+}
@@ -0,0 +1,206 @@
+//! In-RAM shadow of the LEDx_BRIGHTNESS and OUTx_COLOR registers, used for buffered writes
+
+use crate::{Address, Error};
+
+/// Number of independently addressable LP50xx chips
+pub(crate) const MAX_CHIPS: usize = 4;
+/// Number of shadowed registers per chip, the LP5036 has 12 brightness and 36 color registers
+pub(crate) const MAX_REGISTERS: usize = 48;
+/// One slot per independent address plus one for Broadcast
+const SLOTS: usize = MAX_CHIPS + 1;
+/// Slot used for writes to the Broadcast address
+const BROADCAST_SLOT: usize = MAX_CHIPS;
+
+/// Shadow of the output registers. The brightness and color registers span a single block of the register
+/// map, including the reserved LEDx_BRIGHTNESS registers before OUT0_COLOR on the LP5009, LP5018 and
+/// LP5030, so registers are stored by their offset from the first LEDx_BRIGHTNESS register.
+pub(crate) struct FrameBuffer {
+    /// Register values for every chip, and the values pending a Broadcast write
+    registers: [[u8; MAX_REGISTERS]; SLOTS],
+    /// Bit mask of the registers that differ from the hardware
+    dirty: [u64; SLOTS],
+}
+
+impl FrameBuffer {
+    pub(crate) const fn new() -> Self {
+        Self {
+            registers: [[0; MAX_REGISTERS]; SLOTS],
+            dirty: [0; SLOTS],
+        }
+    }
+
+    /// Update the shadow registers for the address
+    /// * `addr` - Address of the LP50xx
+    /// * `offset` - Offset of the first register from the first LEDx_BRIGHTNESS register
+    /// * `values` - The register values
+    /// * `dirty` - Mark the registers as pending a write to the hardware
+    pub(crate) fn update(
+        &mut self,
+        addr: Address,
+        offset: usize,
+        values: &[u8],
+        dirty: bool,
+    ) -> Result<(), Error> {
+        if offset + values.len() > MAX_REGISTERS {
+            return Err(Error::InvalidLed);
+        }
+
+        let mask = ((1u64 << values.len()) - 1) << offset;
+        let slot = get_slot(addr)?;
+
+        if slot == BROADCAST_SLOT {
+            // A Broadcast write supersedes any pending independent write of the same registers
+            for chip in 0..MAX_CHIPS {
+                self.registers[chip][offset..offset + values.len()].copy_from_slice(values);
+                self.dirty[chip] &= !mask;
+            }
+        }
+
+        self.registers[slot][offset..offset + values.len()].copy_from_slice(values);
+        if dirty {
+            self.dirty[slot] |= mask;
+        }
+        Ok(())
+    }
+
+    /// Get the shadow registers for the address
+    /// * `addr` - Address of the LP50xx
+    /// * `offset` - Offset of the first register from the first LEDx_BRIGHTNESS register
+    /// * `len` - Number of registers
+    pub(crate) fn get(&self, addr: Address, offset: usize, len: usize) -> Result<&[u8], Error> {
+        if offset + len > MAX_REGISTERS {
+            return Err(Error::InvalidLed);
+        }
+        Ok(&self.registers[get_slot(addr)?][offset..offset + len])
+    }
+
+    /// Get a shadow register for modification, marking it as pending a write to the hardware
+    /// * `addr` - Address of the LP50xx
+    /// * `offset` - Offset of the register from the first LEDx_BRIGHTNESS register
+    pub(crate) fn get_mut(&mut self, addr: Address, offset: usize) -> Result<&mut u8, Error> {
+        if offset >= MAX_REGISTERS {
+            return Err(Error::InvalidLed);
+        }
+        let slot = get_slot(addr)?;
+        self.dirty[slot] |= 1 << offset;
+        Ok(&mut self.registers[slot][offset])
+    }
+
+    /// Get the shadow registers of consecutive addresses for modification, marking them as pending a write
+    /// to the hardware
+    /// * `first` - Address of the first LP50xx
+    /// * `count` - Number of consecutive addresses
+    /// * `offset` - Offset of the first register from the first LEDx_BRIGHTNESS register
+    /// * `len` - Number of registers of each address
+    pub(crate) fn get_chain_mut(
+        &mut self,
+        first: Address,
+        count: usize,
+        offset: usize,
+        len: usize,
+    ) -> Result<impl Iterator<Item = &mut [u8]>, Error> {
+        let first = get_slot(first)?;
+        if offset + len > MAX_REGISTERS || first + count > SLOTS {
+            return Err(Error::InvalidLed);
+        }
+
+        let mask = ((1u64 << len) - 1) << offset;
+        for dirty in &mut self.dirty[first..first + count] {
+            *dirty |= mask;
+        }
+        Ok(self.registers[first..first + count]
+            .iter_mut()
+            .map(move |registers| &mut registers[offset..offset + len]))
+    }
+
+    /// Find the first run of consecutive dirty registers, Broadcast first so independent writes take precedence.
+    /// Returns the address, the offset of the first register and the register values
+    pub(crate) fn dirty_run(&self) -> Option<(Address, usize, &[u8])> {
+        let slot = (0..SLOTS)
+            .map(|index| (index + BROADCAST_SLOT) % SLOTS)
+            .find(|slot| self.dirty[*slot] != 0)?;
+
+        let dirty = self.dirty[slot];
+        let offset = dirty.trailing_zeros() as usize;
+        let len = (dirty >> offset).trailing_ones() as usize;
+
+        Some((
+            get_address(slot),
+            offset,
+            &self.registers[slot][offset..offset + len],
+        ))
+    }
+
+    /// Mark registers as written to the hardware
+    /// * `addr` - Address of the LP50xx
+    /// * `offset` - Offset of the first register
+    /// * `len` - Number of registers
+    pub(crate) fn clean(&mut self, addr: Address, offset: usize, len: usize) {
+        if let Ok(slot) = get_slot(addr) {
+            self.dirty[slot] &= !(((1u64 << len) - 1) << offset);
+        }
+    }
+}
+
+/// Get the slot index of the address
+fn get_slot(addr: Address) -> Result<usize, Error> {
+    match addr {
+        Address::Broadcast => Ok(BROADCAST_SLOT),
+        Address::Independent(address) if (address as usize) < MAX_CHIPS => Ok(address as usize),
+        Address::Independent(_) => Err(Error::InvalidAddress),
+    }
+}
+
+/// Get the address of the slot index
+fn get_address(slot: usize) -> Address {
+    if slot == BROADCAST_SLOT {
+        Address::Broadcast
+    } else {
+        Address::Independent(slot as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameBuffer;
+    use crate::Address;
+
+    #[test]
+    fn dirty_runs_are_batched() {
+        let mut frame = FrameBuffer::new();
+        frame
+            .update(Address::Independent(1), 4, &[1, 2, 3], true)
+            .unwrap();
+        frame
+            .update(Address::Independent(1), 7, &[4], true)
+            .unwrap();
+        frame
+            .update(Address::Independent(1), 9, &[5], true)
+            .unwrap();
+
+        let (address, offset, values) = frame.dirty_run().unwrap();
+        assert!(matches!(address, Address::Independent(1)));
+        assert_eq!((offset, values), (4, &[1, 2, 3, 4][..]));
+        frame.clean(address, offset, 4);
+
+        let (_, offset, values) = frame.dirty_run().unwrap();
+        assert_eq!((offset, values), (9, &[5][..]));
+        frame.clean(Address::Independent(1), offset, 1);
+        assert!(frame.dirty_run().is_none());
+    }
+
+    #[test]
+    fn broadcast_supersedes_independent_writes() {
+        let mut frame = FrameBuffer::new();
+        frame
+            .update(Address::Independent(0), 0, &[1], true)
+            .unwrap();
+        frame.update(Address::Broadcast, 0, &[2], true).unwrap();
+
+        let (address, offset, values) = frame.dirty_run().unwrap();
+        assert!(matches!(address, Address::Broadcast));
+        assert_eq!((offset, values), (0, &[2][..]));
+        frame.clean(address, offset, 1);
+        assert!(frame.dirty_run().is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! Validated LED and channel indices
+//!
+//! `Led` and `Channel` are checked against the model, and for LEDs the chain length, when constructed,
+//! so bounds errors surface where the index is created rather than at every write:
+//!
+//! ```ignore
+//! const STATUS: u16 = 14;
+//! controller.set_chain_length(2)?;
+//! let status = Led::new(STATUS, Model::LP5012, 2)?;
+//! controller.set(status, 0xFF)?;
+//! ```
+//!
+//! The `set` methods accept either the raw index or the validated newtype through `LedIndex` and
+//! `ChannelIndex`.
+
+use crate::{Error, Model};
+use core::num::{NonZeroU16, NonZeroU8};
+
+/// Index of a monochromatic LED beginning at 1, within the LEDs of a chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Led(NonZeroU16);
+
+impl Led {
+    /// Validate an LED index against the model and the number of chips in the chain
+    /// * `index` - the LED index beginning at 1
+    /// * `model` - The model of the LP50xx
+    /// * `chain_length` - Number of chips in the chain, 1 without continuous addressing
+    pub fn new(index: u16, model: Model, chain_length: u8) -> Result<Self, Error> {
+        let index = NonZeroU16::new(index).ok_or(Error::InvalidLed)?;
+        if index.get() > model.get_pin_count() as u16 * chain_length as u16 {
+            return Err(Error::LedOutOfRange);
+        }
+        Ok(Self(index))
+    }
+
+    /// The LED index beginning at 1
+    pub fn get(self) -> u16 {
+        self.0.get()
+    }
+}
+
+impl From<Led> for u16 {
+    fn from(led: Led) -> Self {
+        led.get()
+    }
+}
+
+/// Index of an RGB channel of a single chip beginning at 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Channel(NonZeroU8);
+
+impl Channel {
+    /// Validate a channel index against the model
+    /// * `index` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+    /// * `model` - The model of the LP50xx
+    pub fn new(index: u8, model: Model) -> Result<Self, Error> {
+        match NonZeroU8::new(index) {
+            Some(index) if index.get() <= model.get_channel_count() => Ok(Self(index)),
+            _ => Err(Error::InvalidChannel),
+        }
+    }
+
+    /// The channel index beginning at 1
+    pub fn get(self) -> u8 {
+        self.0.get()
+    }
+}
+
+impl From<Channel> for u8 {
+    fn from(channel: Channel) -> Self {
+        channel.get()
+    }
+}
+
+/// An LED index beginning at 1, either a raw `u16` or a validated `Led`
+pub trait LedIndex {
+    /// The LED index beginning at 1
+    fn led_index(self) -> u16;
+}
+
+impl LedIndex for u16 {
+    fn led_index(self) -> u16 {
+        self
+    }
+}
+
+impl LedIndex for Led {
+    fn led_index(self) -> u16 {
+        self.get()
+    }
+}
+
+/// A channel index beginning at 1, either a raw `u8` or a validated `Channel`
+pub trait ChannelIndex {
+    /// The channel index beginning at 1
+    fn channel_index(self) -> u8;
+}
+
+impl ChannelIndex for u8 {
+    fn channel_index(self) -> u8 {
+        self
+    }
+}
+
+impl ChannelIndex for Channel {
+    fn channel_index(self) -> u8 {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Channel, Led};
+    use crate::{Error, Model};
+
+    #[test]
+    fn indices_are_validated() {
+        assert_eq!(Led::new(24, Model::LP5012, 2).unwrap().get(), 24);
+        assert!(matches!(
+            Led::new(0, Model::LP5012, 2),
+            Err(Error::InvalidLed)
+        ));
+        assert!(matches!(
+            Led::new(25, Model::LP5012, 2),
+            Err(Error::LedOutOfRange)
+        ));
+        assert_eq!(u8::from(Channel::new(4, Model::LP5012).unwrap()), 4);
+        assert!(matches!(
+            Channel::new(5, Model::LP5012),
+            Err(Error::InvalidChannel)
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+//! Transports used by the LP50xx to communicate with the chip
+
+use crate::Error;
+
+/// A transport capable of delivering register writes (and optionally reads) to the LP50xx.
+/// Implement this trait to drive the LP50xx over custom transports, such as SPI bridges or mocks
+pub trait Lp50xxInterface {
+    /// Write the data payload to the LP50xx
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload to be sent, beginning with the register address
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error>;
+
+    /// Write the data payload to the LP50xx and then read back into the buffer.
+    /// By default reading is not supported by the transport
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload to be sent, typically the register address
+    /// * `buffer` - The buffer the read data is placed in
+    fn write_read(&mut self, address: u8, data: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+        let _ = (address, data, buffer);
+        Err(Error::ReadUnsupported)
+    }
+}
+
+/// Blocking (or with the `async` feature, asynchronous) I2C transport
+pub struct I2cInterface<I2C> {
+    pub(crate) i2c: I2C,
+}
+
+impl<I2C> I2cInterface<I2C> {
+    /// Create the I2C transport
+    /// * `i2c` - I2C interface
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    /// Release the underlying I2C interface
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C> Lp50xxInterface for I2cInterface<I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        self.i2c
+            .write(address, data)
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+
+    fn write_read(&mut self, address: u8, data: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+        self.i2c
+            .write_read(address, data, buffer)
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+}
+
+/// Flexible callback transport, useful for transferring data to a static DMA buffer or queue.
+/// The callback may be a closure capturing state, such as a DMA handle or queue producer.
+/// Reading is not supported by this transport
+pub struct CallbackInterface<CB> {
+    pub(crate) callback: CB,
+}
+
+impl<CB> CallbackInterface<CB>
+where
+    CB: FnMut(u8, &[u8]),
+{
+    /// Create the callback transport
+    /// * `callback` - Callback for custom transmission of the 7bit address and dataframe
+    pub fn new(callback: CB) -> Self {
+        Self { callback }
+    }
+
+    /// Release the underlying callback
+    pub fn release(self) -> CB {
+        self.callback
+    }
+}
+
+impl<CB> Lp50xxInterface for CallbackInterface<CB>
+where
+    CB: FnMut(u8, &[u8]),
+{
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        (self.callback)(address, data);
+        Ok(())
+    }
+}
+
+/// Retry policy applied to every write, so transient bus errors such as NACKs caused by glitches
+/// do not fail a whole animation frame. The default makes a single attempt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub(crate) attempts: u8,
+    pub(crate) delay: Option<(u32, fn(u32))>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Create a retry policy without a delay between attempts
+    /// * `attempts` - Total number of attempts of each write, including the first
+    pub fn new(attempts: u8) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            delay: None,
+        }
+    }
+
+    /// Wait between attempts, giving the bus time to settle
+    /// NOTE: The asynchronous driver never calls the blocking delay function, it awaits
+    /// `Lp50xxAsyncInterface::retry_delay` of the transport instead, which does not wait by default
+    /// * `delay_us` - Time waited between attempts in microseconds
+    /// * `delay` - Blocking delay function, called with `delay_us`
+    pub fn with_delay(mut self, delay_us: u32, delay: fn(u32)) -> Self {
+        self.delay = Some((delay_us, delay));
+        self
+    }
+}
+
+/// Bus health counters of the driver, for reporting through telemetry. The counters saturate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BusStats {
+    /// Writes which failed after every attempt
+    pub failed_writes: u32,
+    /// Attempts repeated after an error
+    pub retries: u32,
+    /// Writes which succeeded after at least one error
+    pub recovered: u32,
+    /// Writes skipped as their address was not acknowledged, with NACK tolerance enabled
+    pub skipped: u32,
+    /// Bit mask of the independent addresses which were not acknowledged, with NACK tolerance enabled
+    pub nacked_chips: u8,
+}
@@ -1,45 +1,307 @@
 //! # lp50xx library
-//! A small library for using the Texas Instruments LP5009 and LP5012 LED drivers
+//! A small library for using the Texas Instruments LP5009, LP5012, LP5018, LP5024, LP5030 and LP5036 LED drivers
 
 #![no_std]
 #![deny(warnings)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use color::ColorOrder;
 use core::marker::PhantomData;
+use core::ops::RangeInclusive;
 use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::OutputPin;
+use framebuffer::{FrameBuffer, MAX_CHIPS, MAX_REGISTERS};
+
+#[macro_use]
+mod macros;
+
+pub mod animation;
+#[cfg(feature = "async")]
+mod asynch;
+pub mod chain;
+pub mod color;
+mod config;
+pub mod dither;
+mod effects;
+#[cfg(feature = "embassy")]
+pub mod embassy;
+pub mod encoder;
+mod framebuffer;
+pub mod gamma;
+mod index;
+pub mod interface;
+#[cfg(feature = "linux")]
+pub mod linux;
+#[cfg(any(feature = "libm", feature = "micromath"))]
+mod math;
+pub mod matrix;
+pub mod meter;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod mux;
+#[cfg(feature = "nb")]
+mod nonblocking;
+#[cfg(feature = "heapless")]
+pub mod queue;
+pub mod registers;
+mod scene;
+mod variant;
+pub mod view;
+pub mod zone;
+
+#[cfg(feature = "async")]
+pub use asynch::Lp50xxAsyncInterface;
+pub use config::{DeviceConfig, LedConfig, Timing};
+pub use index::{Channel, ChannelIndex, Led, LedIndex};
+pub use interface::{BusStats, CallbackInterface, I2cInterface, Lp50xxInterface, RetryPolicy};
+#[cfg(feature = "nb")]
+pub use nonblocking::Lp50xxNbInterface;
+pub use scene::Scene;
+pub use variant::{Lp50xxVariant, Variant};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
-    /// Generic communication Error with blocking I2C
-    CommError,
-    /// Neither the I2C or asynchronous transfer callback defined
-    NoInterfaceDefined,
+    /// Communication Error with I2C, the kind of bus error is preserved so NACKs can be
+    /// distinguished from arbitration loss or other bus faults
+    CommError(embedded_hal::i2c::ErrorKind),
     /// An error setting the Enable pin high or low
     EnableLine,
+    /// Reading registers is not supported by the transport, such as the asynchronous transfer callback
+    ReadUnsupported,
+    /// The address is not one of the 4 dedicated addresses, or Broadcast was used for a read
+    InvalidAddress,
+    /// The RGB channel index is 0 or exceeds the channels of the model
+    InvalidChannel,
+    /// The LED index is 0 or exceeds the pins of the model
+    InvalidLed,
+    /// The LED index is beyond the chips of the chain when using continuous addressing
+    LedOutOfRange,
+    /// The LED is masked and cannot be set, see `set_led_mask`
+    LedMasked,
+    /// An error driving the SCL line during bus recovery
+    BusRecovery,
+    /// Every slot of the animator or dither, or the buffer of the frame encoder, is in use
+    NoFreeSlot,
+    /// The register is beyond the register file of the model
+    InvalidRegister,
+    /// A register read back after a write differs from the written value, see `set_verify_writes`
+    VerifyFailed { reg: u8, expected: u8, got: u8 },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::CommError(kind) => write!(f, "I2C communication error: {}", kind),
+            Error::EnableLine => f.write_str("error setting the enable line"),
+            Error::ReadUnsupported => f.write_str("reading is not supported by the transport"),
+            Error::InvalidAddress => f.write_str("invalid address"),
+            Error::InvalidChannel => f.write_str("invalid channel"),
+            Error::InvalidLed => f.write_str("invalid LED"),
+            Error::LedOutOfRange => f.write_str("LED beyond the chips of the chain"),
+            Error::LedMasked => f.write_str("LED is masked"),
+            Error::BusRecovery => f.write_str("error driving the SCL line during bus recovery"),
+            Error::NoFreeSlot => f.write_str("no free slot"),
+            Error::InvalidRegister => f.write_str("register beyond the register file"),
+            Error::VerifyFailed { reg, expected, got } => write!(
+                f,
+                "register {:#04x} read back as {:#04x}, expected {:#04x}",
+                reg, got, expected
+            ),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Number of registers in the largest register file, of the LP5030 and LP5036
+const MAX_REGISTER_COUNT: usize = registers::lp5036::RESET as usize + 1;
+
+/// Number of words of the LED mask, one bit for every register of every chip
+const LED_MASK_WORDS: usize = MAX_CHIPS * MAX_REGISTERS / 64;
+
 /// Supported Texas Instruments LP50XX models
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Model {
     /// 9 pin controller
     LP5009,
     /// 12 pin controller
     LP5012,
+    /// 18 pin controller
+    LP5018,
+    /// 24 pin controller
+    LP5024,
+    /// 30 pin controller
+    LP5030,
+    /// 36 pin controller
+    LP5036,
+    /// A family member described through `Lp50xxVariant`, see `Model::from_variant`
+    Custom(Variant),
 }
 
 impl Model {
+    /// Create the model of a family member described through `Lp50xxVariant`
+    /// * `variant` - The description of the family member
+    pub fn from_variant<V: Lp50xxVariant>(variant: &V) -> Result<Model, Error> {
+        Variant::new(variant).map(Model::Custom)
+    }
+
     /// Get the pin count for the Model
-    fn get_pin_count(&self) -> u8 {
+    pub fn get_pin_count(&self) -> u8 {
         match *self {
             Model::LP5009 => 9,
             Model::LP5012 => 12,
+            Model::LP5018 => 18,
+            Model::LP5024 => 24,
+            Model::LP5030 => 30,
+            Model::LP5036 => 36,
+            Model::Custom(variant) => variant.pin_count,
+        }
+    }
+
+    /// Get the RGB channel count for the Model
+    fn get_channel_count(&self) -> u8 {
+        self.get_pin_count() / 3
+    }
+
+    /// Get the number of RGB channels, three pins each
+    pub fn rgb_channel_count(&self) -> u8 {
+        self.get_channel_count()
+    }
+
+    /// Get the address of the OUT0_COLOR register, the OUTx_COLOR registers of every pin follow consecutively
+    pub fn out_color_base(&self) -> u8 {
+        self.get_color_base()
+    }
+
+    /// Get the address of the LED0_BRIGHTNESS register, the LEDx_BRIGHTNESS registers of every RGB channel
+    /// follow consecutively
+    pub fn brightness_base(&self) -> u8 {
+        self.get_brightness_base()
+    }
+
+    /// Get the address of the first LEDx_BRIGHTNESS register
+    fn get_brightness_base(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => registers::lp5012::LED0_BRIGHTNESS,
+            Model::LP5018 | Model::LP5024 => registers::lp5024::LED0_BRIGHTNESS,
+            Model::LP5030 | Model::LP5036 => registers::lp5036::LED0_BRIGHTNESS,
+            Model::Custom(variant) => variant.brightness_base,
+        }
+    }
+
+    /// Get the address of the first OUTx_COLOR register
+    fn get_color_base(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => registers::lp5012::OUT0_COLOR,
+            Model::LP5018 | Model::LP5024 => registers::lp5024::OUT0_COLOR,
+            Model::LP5030 | Model::LP5036 => registers::lp5036::OUT0_COLOR,
+            Model::Custom(variant) => variant.color_base,
+        }
+    }
+
+    /// Get the offset of the OUT0_COLOR register from LED0_BRIGHTNESS. The LP5009, LP5018 and LP5030 have
+    /// reserved LEDx_BRIGHTNESS registers in between, so the offset can exceed the channel count
+    fn get_color_offset(&self) -> usize {
+        (self.get_color_base() - self.get_brightness_base()) as usize
+    }
+
+    /// Get the number of registers from LED0_BRIGHTNESS up to and including the last OUTx_COLOR register
+    fn get_output_count(&self) -> usize {
+        self.get_color_offset() + self.get_pin_count() as usize
+    }
+
+    /// Get the address of the BANK_BRIGHTNESS register
+    fn get_bank_brightness_register(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => registers::lp5012::BANK_BRIGHTNESS,
+            Model::LP5018 | Model::LP5024 => registers::lp5024::BANK_BRIGHTNESS,
+            Model::LP5030 | Model::LP5036 => registers::lp5036::BANK_BRIGHTNESS,
+            Model::Custom(variant) => variant.bank_brightness_register,
+        }
+    }
+
+    /// Get the address of the BANK_A_COLOR register, BANK_B_COLOR and BANK_C_COLOR follow consecutively
+    fn get_bank_color_base(&self) -> u8 {
+        self.get_bank_brightness_register() + 1
+    }
+
+    /// Get the address of the RESET register
+    fn get_reset_register(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => registers::lp5012::RESET,
+            Model::LP5018 | Model::LP5024 => registers::lp5024::RESET,
+            Model::LP5030 | Model::LP5036 => registers::lp5036::RESET,
+            Model::Custom(variant) => variant.reset_register,
+        }
+    }
+
+    /// Get the number of registers in the register file, the RESET register is the last
+    fn get_register_count(&self) -> usize {
+        self.get_reset_register() as usize + 1
+    }
+
+    /// Get the 7bit base address used for independent addressing
+    fn get_independent_address_base(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => 0b0010100,
+            Model::LP5018 | Model::LP5024 => 0b0101000,
+            Model::LP5030 | Model::LP5036 => 0b0110000,
+            Model::Custom(variant) => variant.independent_address_base,
+        }
+    }
+
+    /// Get the 7bit broadcast address
+    fn get_broadcast_address(&self) -> u8 {
+        match *self {
+            Model::LP5009 | Model::LP5012 => 0b0001100,
+            Model::LP5018 | Model::LP5024 => 0b0111100,
+            Model::LP5030 | Model::LP5036 => 0b0011100,
+            Model::Custom(variant) => variant.broadcast_address,
         }
     }
 }
 
+impl Lp50xxVariant for Model {
+    fn pin_count(&self) -> u8 {
+        self.get_pin_count()
+    }
+
+    fn brightness_base(&self) -> u8 {
+        self.get_brightness_base()
+    }
+
+    fn color_base(&self) -> u8 {
+        self.get_color_base()
+    }
+
+    fn bank_brightness_register(&self) -> u8 {
+        self.get_bank_brightness_register()
+    }
+
+    fn reset_register(&self) -> u8 {
+        self.get_reset_register()
+    }
+
+    fn independent_address_base(&self) -> u8 {
+        self.get_independent_address_base()
+    }
+
+    fn broadcast_address(&self) -> u8 {
+        self.get_broadcast_address()
+    }
+}
+
 /// The chip select communication address
 /// The addressing is 7bit
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Address {
     /// Broadcast the transferred data to all LP50XX chips on the I2C bus
     Broadcast,
@@ -49,18 +311,48 @@ pub enum Address {
 }
 
 impl Address {
+    /// Create an independent address, such as one read from configuration data
+    /// * `address` - The state of the ADDR pins, 0b00, 0b01, 0b10 or 0b11
+    pub fn try_new(address: u8) -> Result<Address, Error> {
+        if address > 3 {
+            return Err(Error::InvalidAddress);
+        }
+        Ok(Address::Independent(address))
+    }
+
     /// Return the u8 payload data for the address specifier, this data can sent down the wire to the LP50XX to
     /// specifiy the desired chip
     /// NOTE: The directional bit is not included in the addressing and should be included in the i2c driver implementation
+    /// NOTE: This uses the LP5009 and LP5012 address map
+    ///
+    /// # Panics
+    /// If an independent address is not one of the 4 dedicated addresses
+    #[deprecated(
+        note = "use `into_u8_for_model`, which supports every model and returns an error instead of panicking"
+    )]
     pub fn into_u8(self) -> u8 {
+        match self.into_u8_for_model(Model::LP5012) {
+            Ok(address) => address,
+            Err(_) => {
+                panic!("LP50XX only supports 4 dedicated addresses, 0b00, 0b01, 0b10 or 0b11")
+            }
+        }
+    }
+
+    /// Return the u8 payload data for the address specifier using the address map of the given model,
+    /// this data can be sent down the wire to the LP50XX to specify the desired chip
+    /// NOTE: The directional bit is not included in the addressing and should be included in the i2c driver implementation
+    /// * `model` - The model of the addressed LP50xx
+    pub fn into_u8_for_model(self, model: Model) -> Result<u8, Error> {
         match self {
             Address::Independent(address) => {
+                // LP50XX only supports 4 dedicated addresses, 0b00, 0b01, 0b10 or 0b11
                 if address > 3 {
-                    panic!("LP50XX only supports 4 dedicated addresses, 0b00, 0b01, 0b10 or 0b11")
+                    return Err(Error::InvalidAddress);
                 }
-                return 0b00010100 | address;
+                Ok(model.get_independent_address_base() | address)
             }
-            Address::Broadcast => 0b0001100,
+            Address::Broadcast => Ok(model.get_broadcast_address()),
         }
     }
 }
@@ -68,9 +360,143 @@ impl Address {
 /// Default Mode
 pub struct DefaultMode {}
 
+/// Typestate of an LP50XX which has not been enabled yet, no commands can be sent
+pub struct Disabled;
+
+/// Typestate of an enabled LP50XX, which accepts commands
+pub struct Enabled;
+
+/// Placeholder for boards where the EN line is tied high in hardware. The LP50XX is then enabled
+/// solely through the CHIP_EN bit of the DEVICE_CONFIG0 register
+pub struct NoEnable;
+
+impl embedded_hal::digital::ErrorType for NoEnable {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for NoEnable {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// An enable line owning a delay provider, so the driver times its own enable and reset sequences with
+/// `enable_timed` and `reset_timed` instead of borrowing a delay provider on every call
+///
+/// ```ignore
+/// let controller = LP50xx::init_with_i2c(Model::LP5012, i2c, TimedEnable::new(en, delay));
+/// let mut controller = controller.into_color_mode().enable_timed()?;
+/// ```
+pub struct TimedEnable<EN, DELAY> {
+    pin: EN,
+    delay: DELAY,
+}
+
+impl<EN, DELAY> TimedEnable<EN, DELAY> {
+    /// * `pin` - The enable line, or `NoEnable` when the EN line is tied high
+    /// * `delay` - The delay provider owned by the driver
+    pub fn new(pin: EN, delay: DELAY) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Release the enable line and the delay provider
+    pub fn into_inner(self) -> (EN, DELAY) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<EN, DELAY> embedded_hal::digital::ErrorType for TimedEnable<EN, DELAY>
+where
+    EN: OutputPin,
+{
+    type Error = EN::Error;
+}
+
+impl<EN, DELAY> OutputPin for TimedEnable<EN, DELAY>
+where
+    EN: OutputPin,
+{
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()
+    }
+}
+
+/// The enable line of the driver. With the `disable-on-drop` feature the line is pulled low when the
+/// driver is dropped, shutting down every output rather than leaving the LEDs frozen at their last value
+struct EnableLine<EN> {
+    /// The pin, only taken when the driver is released
+    pin: Option<EN>,
+    /// Pulls the pin low, captured while the `OutputPin` bound is known since `Drop` cannot require it
+    #[cfg(feature = "disable-on-drop")]
+    disable: fn(&mut EN),
+}
+
+impl<EN> EnableLine<EN>
+where
+    EN: OutputPin,
+{
+    fn new(pin: EN) -> Self {
+        Self {
+            pin: Some(pin),
+            #[cfg(feature = "disable-on-drop")]
+            disable: |pin| {
+                pin.set_low().ok();
+            },
+        }
+    }
+}
+
+impl<EN> EnableLine<EN> {
+    /// Release the pin without disabling the outputs
+    fn into_inner(mut self) -> EN {
+        match self.pin.take() {
+            Some(pin) => pin,
+            None => unreachable!("the pin is only taken when released"),
+        }
+    }
+}
+
+impl<EN> core::ops::Deref for EnableLine<EN> {
+    type Target = EN;
+
+    fn deref(&self) -> &EN {
+        match &self.pin {
+            Some(pin) => pin,
+            None => unreachable!("the pin is only taken when released"),
+        }
+    }
+}
+
+impl<EN> core::ops::DerefMut for EnableLine<EN> {
+    fn deref_mut(&mut self) -> &mut EN {
+        match &mut self.pin {
+            Some(pin) => pin,
+            None => unreachable!("the pin is only taken when released"),
+        }
+    }
+}
+
+#[cfg(feature = "disable-on-drop")]
+impl<EN> Drop for EnableLine<EN> {
+    fn drop(&mut self) {
+        if let Some(pin) = &mut self.pin {
+            (self.disable)(pin);
+        }
+    }
+}
+
 /// ColorMode allows the user to configure the LEDs in fashion that is suitable if the LED supports RGB
 pub struct ColorMode {}
 impl ColorMode {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {}
     }
@@ -80,35 +506,89 @@ impl ColorMode {
 pub struct MonochromaticMode {}
 
 impl MonochromaticMode {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {}
     }
 }
 
-/// The LP50XX (LP5009 or LP5012) is a 9 or 12 pin LED controller by Texas Instruments
-pub struct LP50xx<MODE, I2C, EN> {
-    /// I2C interface, used specifically for blocking writes to the LP50XX
-    interface: Option<I2C>,
+/// The LP50XX (LP5009, LP5012, LP5018, LP5024, LP5030 or LP5036) is a 9 to 36 pin LED controller by Texas Instruments
+pub struct LP50xx<MODE, IFACE, EN, STATE> {
+    /// Transport used to communicate with the LP50XX, such as blocking I2C or a flexible callback
+    interface: IFACE,
     /// Enable line
-    enable: EN,
-    /// Asynchronous transfer callback, useful for transferring data to a static DMA buffer or queue
-    /// When the blocking I2C interface is provided, this transfer_callback value is ignored
-    transfer_callback: Option<fn(addr: Address, data: &[u8])>,
+    enable: EnableLine<EN>,
     /// Continuous addressing allows intuitive numbering of banks/leds when multiple LP50XX chips are used
     /// in a daisy-chain configuration. For example, for the LP5009 if specifying the 9th led, the address will be 0x00
     /// but when specifying the 10th led, the address will be 0x01 (the next chip address)
     continuous_addressing: bool,
+    /// Number of chips in the daisy-chain used by continuous addressing, a single chip by default
+    chain_length: u8,
     /// Chip select address (ignored when continuous addressing is set to true)
     active_address: Address,
     /// The Display Mode of the LP50XX, which modifies the API for intuitive use for RGB Color mode or Monochromatic mode
     mode: PhantomData<MODE>,
-    /// Model, can either be the LP5009 (9 pin) or LP5012 (12 pin)
+    /// Whether the LP50XX has been enabled, commands can only be sent once enabled
+    state: PhantomData<STATE>,
+    /// Model, can either be the LP5009 (9 pin), LP5012 (12 pin), LP5018 (18 pin), LP5024 (24 pin),
+    /// LP5030 (30 pin) or LP5036 (36 pin)
     model: Model,
-    /// Brightness factor. Note: Only used for monochromatic mode.
+    /// Brightness factor, scales the channel brightness in color mode and the luminosity in monochromatic mode
     brightness_factor: f32,
+    /// Optional gamma correction table applied to color and luminosity values
+    gamma: Option<&'static [u8; 256]>,
+    /// The last configuration written to DEVICE_CONFIG1
+    config: DeviceConfig,
+    /// Buffered mode defers writes of the brightness and color registers until `flush` is called
+    buffered: bool,
+    /// Shadow of the brightness and color registers
+    frame: FrameBuffer,
+    /// Number of steps used by the blocking fades
+    fade_steps: u16,
+    /// Order in which the LEDs of each RGB channel are wired to the OUTx pins
+    color_order: ColorOrder,
+    /// Base of the independent addresses, overriding the address map of the model
+    base_address: Option<u8>,
+    /// Broadcast address, overriding the address map of the model
+    broadcast_address: Option<u8>,
+    /// Read back every register written to an independent address
+    verify_writes: bool,
+    /// Retry policy applied to every write
+    retry: RetryPolicy,
+    /// Bus health counters
+    stats: BusStats,
+    /// Handler notified of chips found reset before a flush, None when power loss detection is disabled
+    power_loss: Option<fn(Address)>,
+    /// Red, green and blue calibration scaling factors applied to every channel, 255 being unity
+    white_balance: [u8; 3],
+    /// Optional red, green and blue calibration scaling factors of each channel, indexed from channel 1
+    channel_balance: Option<&'static [[u8; 3]]>,
+    /// Drive each monochromatic LED through an LEDx_BRIGHTNESS register instead of an OUTx_COLOR register
+    brightness_addressing: bool,
+    /// Bit mask of the monochromatic LEDs which are never lit, bit 0 being LED 1
+    led_mask: [u64; LED_MASK_WORDS],
+    /// Thermal derating in percent, scaling every brightness and luminosity value on top of the brightness factor
+    thermal_limit: u8,
+    /// Ambient light scaling factor applied on top of the brightness factor, 255 being unity
+    ambient_scale: u8,
+    /// Minimum interval between the frames written by `flush` in buffered mode in milliseconds, and the
+    /// monotonic millisecond clock measuring it, None when flushes are not throttled
+    frame_limit: Option<(u32, fn() -> u32)>,
+    /// Clock reading of the last frame written by `flush` when throttled
+    last_frame: Option<u32>,
+    /// Bit mask of the chips put in standby through CHIP_EN, bit 0 being independent address 0
+    standby_chips: u8,
+    /// Observer called with every transaction written, None when disabled
+    write_observer: Option<fn(Address, &[u8])>,
+    /// Maximum length of a transaction including the register address, longer writes are split
+    max_write_len: usize,
+    /// Skip writes whose address is not acknowledged instead of returning the error
+    nack_tolerant: bool,
+    /// Delays of the enable and reset sequences
+    timing: Timing,
 }
 
-impl<I2C, EN> LP50xx<DefaultMode, I2C, EN>
+impl<I2C, EN> LP50xx<DefaultMode, I2cInterface<I2C>, EN, Disabled>
 where
     EN: OutputPin,
 {
@@ -116,283 +596,2799 @@ where
     /// * `model` - The model of the LP50xx
     /// * `i2c` - I2C interface for blocking tranmission
     /// * `en` - The enable line
-    pub fn init_with_i2c(model: Model, i2c: I2C, mut en: EN) -> Self {
-        en.set_low().ok();
-
-        Self {
-            interface: Some(i2c),
-            enable: en,
-            transfer_callback: None,
-            model,
-            active_address: Address::Broadcast,
-            continuous_addressing: true,
-            mode: PhantomData,
-            brightness_factor: 1.0,
-        }
+    pub fn init_with_i2c(model: Model, i2c: I2C, en: EN) -> Self {
+        Self::init_with_interface(model, I2cInterface::new(i2c), en)
     }
+}
 
+impl<CB, EN> LP50xx<DefaultMode, CallbackInterface<CB>, EN, Disabled>
+where
+    EN: OutputPin,
+    CB: FnMut(u8, &[u8]),
+{
     /// Initialize the LP50xx with a flexible asynchronous callback interface
     /// * `model` - The model of the LP50xx
     /// * `en` - The enable line
-    /// * `callback` - Callback for custom transmission of the address and dataframe.
-    pub fn init_with_callback(
-        model: Model,
-        mut en: EN,
-        callback: fn(addr: Address, data: &[u8]),
-    ) -> Self {
+    /// * `callback` - Callback for custom transmission of the 7bit address and dataframe.
+    pub fn init_with_callback(model: Model, en: EN, callback: CB) -> Self {
+        Self::init_with_interface(model, CallbackInterface::new(callback), en)
+    }
+}
+
+impl<IFACE, EN> LP50xx<DefaultMode, IFACE, EN, Disabled>
+where
+    EN: OutputPin,
+{
+    /// Initialize the LP50xx with any transport implementing `Lp50xxInterface`
+    /// * `model` - The model of the LP50xx
+    /// * `interface` - Transport used to communicate with the LP50xx
+    /// * `en` - The enable line
+    pub fn init_with_interface(model: Model, interface: IFACE, mut en: EN) -> Self {
         en.set_low().ok();
 
         Self {
-            interface: None,
-            enable: en,
-            transfer_callback: Some(callback),
+            interface,
+            enable: EnableLine::new(en),
             model,
             active_address: Address::Broadcast,
             continuous_addressing: true,
+            chain_length: 1,
             mode: PhantomData,
+            state: PhantomData,
             brightness_factor: 1.0,
+            gamma: None,
+            config: DeviceConfig::default(),
+            buffered: false,
+            frame: FrameBuffer::new(),
+            fade_steps: 32,
+            color_order: ColorOrder::Rgb,
+            base_address: None,
+            broadcast_address: None,
+            verify_writes: false,
+            retry: RetryPolicy::default(),
+            stats: BusStats::default(),
+            power_loss: None,
+            white_balance: [0xFF; 3],
+            channel_balance: None,
+            brightness_addressing: false,
+            led_mask: [0; LED_MASK_WORDS],
+            thermal_limit: 100,
+            ambient_scale: 0xFF,
+            frame_limit: None,
+            last_frame: None,
+            standby_chips: 0,
+            write_observer: None,
+            max_write_len: usize::MAX,
+            nack_tolerant: false,
+            timing: Timing::default(),
         }
     }
+}
 
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE>
+where
+    EN: OutputPin,
+{
     /// Set continuous addressing
     /// * `state` - Continuous addressing enable
     pub fn set_continuous_addressing(&mut self, state: bool) {
         self.continuous_addressing = state;
     }
 
-    /// Set the active chip address: Broadcast, 0b00, 0b01, 0b10 or 0b11.
-    /// * `address` - Address of the active LP50xx
-    pub fn set_active_address(&mut self, address: Address) {
-        self.active_address = address;
+    /// Get whether continuous addressing is enabled
+    pub fn continuous_addressing(&self) -> bool {
+        self.continuous_addressing
     }
 
-    /// Release underlying resources back to initiator
-    pub fn release(self) -> (Option<I2C>, EN) {
-        (self.interface, self.enable)
+    /// Get the number of chips in the daisy-chain used by continuous addressing
+    pub fn chain_length(&self) -> u8 {
+        self.chain_length
     }
-}
 
-impl<MODE, I2C, EN> LP50xx<MODE, I2C, EN>
-where
-    I2C: embedded_hal::i2c::I2c,
-    EN: OutputPin,
-{
-    /// Configure the LP50xx to be in color mode, which is most suitable if the target LEDs support RGB
-    pub fn into_color_mode(self) -> LP50xx<ColorMode, I2C, EN> {
-        self.into_mode::<ColorMode>()
+    /// Get the active chip address
+    pub fn active_address(&self) -> Address {
+        self.active_address
     }
 
-    /// Configure the LP50xx to be in monochromatic mode, which is most suitable if the target LEDs are monochromatic
-    pub fn into_monochromatic_mode(self) -> LP50xx<MonochromaticMode, I2C, EN> {
-        self.into_mode::<MonochromaticMode>()
+    /// Get the model of the LP50xx
+    pub fn model(&self) -> Model {
+        self.model
     }
 
-    /// Helper function to convert the struct appropriately
-    fn into_mode<MODE2>(self) -> LP50xx<MODE2, I2C, EN> {
-        LP50xx {
-            interface: self.interface,
-            enable: self.enable,
-            transfer_callback: self.transfer_callback,
-            active_address: self.active_address,
-            model: self.model,
-            continuous_addressing: self.continuous_addressing,
-            mode: PhantomData,
-            brightness_factor: 1.0,
-        }
+    /// Override the base of the independent addresses, for translated addresses behind a bridge or
+    /// parts with a different address map. The ADDR pin state is combined with the base
+    /// * `base` - Base address such as 0x14, or None to use the address map of the model
+    pub fn set_base_address(&mut self, base: Option<u8>) {
+        self.base_address = base;
     }
 
-    /// Write data to the desired interface. If the i2C interface is provided,
-    /// it will perform a blocking call to I2C and return the result,
-    /// if I2C is not provided, then the asynchronous transfer callback is executed
-    /// * `addr` - Address of the LP50xx
-    /// * `data` - The data payload to be sent
-    fn write(&mut self, addr: Address, data: &[u8]) -> Result<(), Error> {
-        // If there is an i2c interface provided, utilize it in a blocking fashion
-        if self.interface.is_some() {
-            self.interface
-                .as_mut()
-                .unwrap()
-                .write(addr.into_u8(), data)
-                .map_err(|_| Error::CommError)?;
-            return Ok({});
-        }
-
-        if self.transfer_callback.is_some() {
-            self.transfer_callback.unwrap()(addr, data);
-            return Ok({});
-        }
+    /// Override the Broadcast address
+    /// * `address` - Broadcast address such as 0x0C, or None to use the address map of the model
+    pub fn set_broadcast_address(&mut self, address: Option<u8>) {
+        self.broadcast_address = address;
+    }
 
-        return Err(Error::NoInterfaceDefined);
+    /// Read back every register written to an independent address and return `Error::VerifyFailed`
+    /// on a mismatch, for installations with electrically noisy buses. Broadcast writes and the
+    /// reset register cannot be read back and are not verified
+    /// NOTE: Verification requires a transport supporting reads and doubles the bus traffic
+    /// * `enable` - Verify writes
+    pub fn set_verify_writes(&mut self, enable: bool) {
+        self.verify_writes = enable;
     }
 
-    /// Reset the LP50xx
-    /// Currently resetting is only available for Broadcast
-    /// * `delay` - delay provider
-    pub fn reset<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), Error>
-    where
-        DELAY: DelayNs,
-    {
-        self.write(Address::Broadcast, &[0x17, 0xff])?;
-        delay.delay_ms(1);
-        self.enable.set_low().map_err(|_| Error::EnableLine)?;
-        delay.delay_ms(10);
-        self.enable.set_high().map_err(|_| Error::EnableLine)?;
-        delay.delay_ms(10);
-        Ok(())
+    /// Set the retry policy applied to every write. Bus errors and failed verifications are retried,
+    /// the error of the last attempt is returned once every attempt has failed
+    /// * `policy` - The retry policy
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
     }
 
-    /// Enable the LP50xx, this must be executed prior to any commands sent to the LP50xx
-    /// * `delay` - delay provider
-    pub fn enable<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), Error>
-    where
-        DELAY: DelayNs,
-    {
-        self.enable.set_low().map_err(|_| Error::EnableLine)?;
-        delay.delay_ms(1);
-        self.enable.set_high().map_err(|_| Error::EnableLine)?;
-        delay.delay_ms(10);
-        self.write(Address::Broadcast, &[0x00, 0b01000000])
-    }
-
-    /// Configure the LP50xx. For information regarding each of these settings, please consult the datasheet.
-    /// Currently configuring is only available for Broadcast
-    /// * `log_scale` - Logarithmic scale dimming curve
-    /// * `power_save` - Automatic power-saving mode enabled
-    /// * `auto_incr` - The auto-increment feature allows writing or reading several consecutive registers within one transmission.
-    /// * `pwm_dithering` - PWM dithering mode enabled
-    /// * `max_current_option` - Output maximum current enable: IMAX = 35 mA, disable: IMAX = 25.5mA
-    /// * `global_off` - Shut down all LEDs when enabled
-    pub fn configure(
-        &mut self,
-        log_scale: bool,
-        power_save: bool,
-        auto_incr: bool,
-        pwm_dithering: bool,
-        max_current_option: bool,
-        global_off: bool,
-    ) -> Result<(), Error> {
-        let value: u8 = 0x00
-            | (log_scale as u8) << 5
-            | (power_save as u8) << 4
-            | (auto_incr as u8) << 3
-            | (pwm_dithering as u8) << 2
-            | (max_current_option as u8) << 1
-            | (global_off as u8) << 0;
+    /// Set NACK tolerance. When enabled, a write whose address is not acknowledged, such as to an absent or
+    /// failed chip of the chain, is recorded in the bus health counters and skipped instead of aborting the
+    /// whole update. Not applied by the non-blocking `_nb` methods
+    /// * `enable` - NACK tolerance enable
+    pub fn set_nack_tolerant(&mut self, enable: bool) {
+        self.nack_tolerant = enable;
+    }
 
-        self.write(Address::Broadcast, &[0x01, value])
+    /// Treat a write whose address was not acknowledged as skipped when NACK tolerance is enabled, recording
+    /// the address in the bus health counters
+    /// * `addr` - Address of the LP50xx
+    /// * `result` - The result of the write
+    fn tolerate_nack(&mut self, addr: Address, result: Result<(), Error>) -> Result<(), Error> {
+        use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+        match result {
+            Err(Error::CommError(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address | NoAcknowledgeSource::Unknown,
+            ))) if self.nack_tolerant => {
+                self.stats.skipped = self.stats.skipped.saturating_add(1);
+                if let Address::Independent(chip) = addr {
+                    self.stats.nacked_chips |= 1 << chip;
+                }
+                Ok(())
+            }
+            result => result,
+        }
     }
-}
 
-// Color Mode
+    /// Counters of failed writes, retries, recovered errors and skipped writes since initialization or `reset_stats`
+    pub fn stats(&self) -> BusStats {
+        self.stats
+    }
 
-impl<I2C, EN> LP50xx<ColorMode, I2C, EN>
-where
-    I2C: embedded_hal::i2c::I2c,
-    EN: OutputPin,
-{
-    /// Set the channel brightness and RGB values
-    pub fn set(
-        &mut self,
-        mut channel: u8,
-        (brightness, [r, g, b]): (u8, [u8; 3]),
-    ) -> Result<(), Error> {
-        if channel < 1 {
-            panic!("Specified Channel index must be greater than 0");
-        }
+    /// Reset the bus health counters
+    pub fn reset_stats(&mut self) {
+        self.stats = BusStats::default();
+    }
 
-        channel = channel - 1;
+    /// Detect chips reset by a brown-out or power loss before each blocking flush, by reading the CHIP_EN
+    /// bit of every chip in the chain. A reset chip is enabled, configured and its cached outputs are
+    /// written again, after which the handler is called with its address
+    /// NOTE: Detection requires a transport supporting reads and is not performed by `flush_async`
+    /// * `handler` - Notified of every reset chip, or None to disable detection
+    pub fn set_power_loss_handler(&mut self, handler: Option<fn(Address)>) {
+        self.power_loss = handler;
+    }
 
-        let bright_addr = 0x07 + channel as u8;
-        let color_addr = 0x0b + (channel as u8) * 3;
-        self.write(self.active_address, &[bright_addr, brightness])?;
-        self.write(self.active_address, &[color_addr, r, g, b])?;
-        Ok(())
+    /// Set an observer called with the address and payload of every transaction written, with any transport
+    /// and including retries. Useful for logging, protocol capture and correlating LED commands
+    /// with logic analyzer traces
+    /// * `observer` - Called with the address and payload of each transaction
+    pub fn set_write_observer(&mut self, observer: fn(Address, &[u8])) {
+        self.write_observer = Some(observer);
     }
-}
 
-// Monochromatic Mode
+    /// Set the maximum length of a transaction including the register address, for I2C peripherals or DMA
+    /// setups limiting the transfer length. Longer auto-increment writes are split into consecutive chunks,
+    /// which is not applied by the non-blocking `_nb` methods. The minimum is 2, a register and a value
+    /// * `len` - Maximum transaction length in bytes
+    pub fn set_max_write_len(&mut self, len: usize) {
+        self.max_write_len = len.max(2);
+    }
 
-impl<I2C, EN> LP50xx<MonochromaticMode, I2C, EN>
-where
-    I2C: embedded_hal::i2c::I2c,
-    EN: OutputPin,
-{
-    /// Set the brightness factor which will dim the output
-    /// The maximum value is 1.0 (100%) and the minimum is 0.01 (1%)
-    /// * `factor` - Brightness factor
-    pub fn set_brightness_factor(&mut self, mut factor: f32) {
-        if factor < 0.01 {
-            factor = 0.01;
-        } else if factor > 1.0 {
-            factor = 1.0;
-        }
-        self.brightness_factor = factor;
+    /// Get the maximum length of a transaction including the register address
+    pub fn max_write_len(&self) -> usize {
+        self.max_write_len
     }
 
-    /// Get the configured brightness factor
-    pub fn brightness_factor(&self) -> f32 {
-        self.brightness_factor
+    /// Stop observing the transactions written
+    pub fn clear_write_observer(&mut self) {
+        self.write_observer = None;
     }
 
-    /// Set the desired LED value
-    /// * `led` - the LED index beginning at 1
-    /// * `value` - luminosity value
-    pub fn set(&mut self, led: u8, value: u8) -> Result<(), Error> {
-        if led == 0 {
-            panic!("Specified LED index must be greater than 0");
+    /// Notify the write observer of a transaction, logging it at trace level with the `defmt-trace` feature
+    /// * `addr` - Address of the LP50xx
+    /// * `data` - The data payload
+    fn observe_write(&self, addr: Address, data: &[u8]) {
+        #[cfg(feature = "defmt-trace")]
+        if let Some((register, values)) = data.split_first() {
+            defmt::trace!(
+                "write {} register {=u8:#04x} values {=[u8]:#04x}",
+                addr,
+                *register,
+                values
+            );
         }
-        if !self.continuous_addressing && led > self.model.get_pin_count() {
-            panic!("Specified LED is not supported");
+        if let Some(observer) = self.write_observer {
+            observer(addr, data);
         }
+    }
 
-        // In monochromatic mode, brightness is no longer applicable
-        let led_base_address = 0x0B;
-
-        let (address, pin_offset) = if self.continuous_addressing {
-            let addr_offset = get_led_address_offset(led, self.model);
-            let addr = Address::Independent(addr_offset);
-            let pin_offset = led - (addr_offset * self.model.get_pin_count());
-            (addr, pin_offset)
-        } else {
-            (self.active_address, led)
-        };
+    /// Set the number of chips in the daisy-chain used by continuous addressing, addressed
+    /// consecutively from 0b00. The chain is a single chip by default, LEDs beyond the chain
+    /// return `Error::LedOutOfRange`
+    /// * `length` - Number of chips in the chain, between 1 and 4
+    pub fn set_chain_length(&mut self, length: u8) -> Result<(), Error> {
+        if length == 0 || length as usize > MAX_CHIPS {
+            return Err(Error::InvalidAddress);
+        }
+        self.chain_length = length;
+        Ok(())
+    }
 
-        let result = (value as f32 * self.brightness_factor) as u8;
+    /// Set the active chip address: Broadcast, 0b00, 0b01, 0b10 or 0b11.
+    /// * `address` - Address of the active LP50xx
+    pub fn set_active_address(&mut self, address: Address) {
+        self.active_address = address;
+    }
 
-        self.write(address, &[led_base_address + (pin_offset - 1), result])?;
-        Ok(())
+    /// Release underlying resources back to initiator
+    pub fn release(self) -> (IFACE, EN) {
+        (self.interface, self.enable.into_inner())
     }
 }
 
-/// Get the led offset address for the given led index and the model
-/// * `led_index` - the LED index beginning at 1
-/// * `model` - Model number of the LP50xx
-fn get_led_address_offset(led_index: u8, model: Model) -> u8 {
-    let length = model.get_pin_count();
-
-    if led_index <= length {
-        return 0x00;
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE> {
+    /// Get the number of RGB channels of each chip
+    pub fn num_channels(&self) -> u8 {
+        self.model.get_channel_count()
     }
-    if led_index > length && led_index <= (length * 2) {
-        return 0x01;
+
+    /// Get the number of individually addressable LEDs, every LED of the chain with continuous addressing
+    /// or the LEDs of a single chip otherwise
+    pub fn num_leds(&self) -> u16 {
+        let (_, leds) = self.get_led_registers();
+        if self.continuous_addressing {
+            leds as u16 * self.chain_length as u16
+        } else {
+            leds as u16
+        }
+    }
+
+    /// Get the maximum number of chips in a chain, one per independent address
+    pub fn max_chain_len(&self) -> u8 {
+        MAX_CHIPS as u8
+    }
+
+    /// Configure the LP50xx to be in color mode, which is most suitable if the target LEDs support RGB.
+    /// The mode can be switched at any time, retaining the state, configuration and cached outputs
+    pub fn into_color_mode(self) -> LP50xx<ColorMode, IFACE, EN, STATE> {
+        self.into_mode::<ColorMode>()
+    }
+
+    /// Configure the LP50xx to be in monochromatic mode, which is most suitable if the target LEDs are monochromatic.
+    /// The mode can be switched at any time, retaining the state, configuration and cached outputs
+    pub fn into_monochromatic_mode(self) -> LP50xx<MonochromaticMode, IFACE, EN, STATE> {
+        self.into_mode::<MonochromaticMode>()
+    }
+
+    /// Leave color or monochromatic mode, retaining the state, configuration and cached outputs
+    pub fn into_default_mode(self) -> LP50xx<DefaultMode, IFACE, EN, STATE> {
+        self.into_mode::<DefaultMode>()
+    }
+
+    /// Helper function to convert the struct appropriately
+    fn into_mode<MODE2>(self) -> LP50xx<MODE2, IFACE, EN, STATE> {
+        self.convert(|enable| enable)
+    }
+
+    /// Helper function to transition between the enabled and disabled states
+    fn into_state<STATE2>(self) -> LP50xx<MODE, IFACE, EN, STATE2> {
+        self.convert(|enable| enable)
+    }
+
+    /// Helper function to rebuild the struct with another mode, state or enable line
+    /// * `enable` - Converts the enable line
+    fn convert<MODE2, EN2, STATE2>(
+        self,
+        enable: impl FnOnce(EnableLine<EN>) -> EnableLine<EN2>,
+    ) -> LP50xx<MODE2, IFACE, EN2, STATE2> {
+        LP50xx {
+            interface: self.interface,
+            enable: enable(self.enable),
+            active_address: self.active_address,
+            model: self.model,
+            continuous_addressing: self.continuous_addressing,
+            chain_length: self.chain_length,
+            mode: PhantomData,
+            state: PhantomData,
+            brightness_factor: self.brightness_factor,
+            gamma: self.gamma,
+            config: self.config,
+            buffered: self.buffered,
+            frame: self.frame,
+            fade_steps: self.fade_steps,
+            color_order: self.color_order,
+            base_address: self.base_address,
+            broadcast_address: self.broadcast_address,
+            verify_writes: self.verify_writes,
+            retry: self.retry,
+            stats: self.stats,
+            power_loss: self.power_loss,
+            white_balance: self.white_balance,
+            channel_balance: self.channel_balance,
+            brightness_addressing: self.brightness_addressing,
+            led_mask: self.led_mask,
+            thermal_limit: self.thermal_limit,
+            ambient_scale: self.ambient_scale,
+            frame_limit: self.frame_limit,
+            last_frame: self.last_frame,
+            standby_chips: self.standby_chips,
+            write_observer: self.write_observer,
+            max_write_len: self.max_write_len,
+            nack_tolerant: self.nack_tolerant,
+            timing: self.timing,
+        }
+    }
+
+    /// Set the brightness factor which will dim the output. In color mode the channel brightness is scaled,
+    /// in monochromatic mode the LED luminosity is scaled.
+    /// The maximum value is 1.0 (100%) and the minimum is 0.01 (1%)
+    /// * `factor` - Brightness factor
+    pub fn set_brightness_factor(&mut self, factor: f32) {
+        self.brightness_factor = factor.clamp(0.01, 1.0);
+    }
+
+    /// Get the configured brightness factor
+    pub fn brightness_factor(&self) -> f32 {
+        self.brightness_factor
+    }
+
+    /// Set the thermal limit, derating every brightness and luminosity value written afterwards on top of the
+    /// brightness factor. Intended to be fed periodically from a temperature sensor, so the LEDs are kept
+    /// within the thermal budget without changing any call site. Outputs already written are not changed
+    /// until they are next set. The maximum value is 100 (no derating)
+    /// * `percent` - Percentage of the brightness allowed
+    pub fn set_thermal_limit(&mut self, percent: u8) {
+        self.thermal_limit = percent.min(100);
+    }
+
+    /// Get the configured thermal limit in percent
+    pub fn thermal_limit(&self) -> u8 {
+        self.thermal_limit
+    }
+
+    /// Set the ambient light scaling factor, dimming every brightness and luminosity value written afterwards
+    /// on top of the brightness factor. Intended to be fed from an ambient light sensor, so day and night
+    /// dimming is handled by the driver. Outputs already written are not changed until they are next set
+    /// * `scale` - Scaling factor, 255 being unity
+    pub fn set_ambient_scale(&mut self, scale: u8) {
+        self.ambient_scale = scale;
+    }
+
+    /// Get the configured ambient light scaling factor
+    pub fn ambient_scale(&self) -> u8 {
+        self.ambient_scale
+    }
+
+    /// Scale the value by the brightness factor, the ambient light scaling factor and the thermal limit
+    /// * `value` - brightness or luminosity value
+    fn scale(&self, value: u8) -> u8 {
+        let scaled = (value as f32 * self.brightness_factor) as u32;
+        (scaled * self.ambient_scale as u32 * self.thermal_limit as u32 / (255 * 100)) as u8
+    }
+
+    /// Set the gamma correction table applied to color and luminosity values before writing,
+    /// such as `gamma::GAMMA_2_2`. Useful when the logarithmic scale dimming curve is disabled
+    /// * `table` - Gamma correction lookup table, or None to disable gamma correction
+    pub fn set_gamma(&mut self, table: Option<&'static [u8; 256]>) {
+        self.gamma = table;
+    }
+
+    /// Apply the gamma correction table to the value
+    /// * `value` - color or luminosity value
+    fn correct(&self, value: u8) -> u8 {
+        match self.gamma {
+            Some(table) => table[value as usize],
+            None => value,
+        }
+    }
+
+    /// Set the order in which the LEDs of each RGB channel are wired to the OUTx pins, such as GRB.
+    /// Color values are always given in RGB order and reordered before writing
+    /// * `order` - Color order of the board
+    pub fn set_color_order(&mut self, order: ColorOrder) {
+        self.color_order = order;
+    }
+
+    /// The color order of the board
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Set the red, green and blue calibration scaling factors applied to the color values of every channel,
+    /// so panels built from different LED bins can be matched to a uniform white point
+    /// * `factors` - Red, green and blue scaling factors, 255 leaves the color unchanged
+    pub fn set_white_balance(&mut self, factors: [u8; 3]) {
+        self.white_balance = factors;
+    }
+
+    /// The red, green and blue calibration scaling factors applied to every channel
+    pub fn white_balance(&self) -> [u8; 3] {
+        self.white_balance
+    }
+
+    /// Set the red, green and blue calibration scaling factors of each channel, applied on top of the white balance.
+    /// Channels beyond the end of the table are not calibrated individually
+    /// * `table` - Scaling factors of each channel beginning at channel 1, or None to disable
+    pub fn set_channel_white_balance(&mut self, table: Option<&'static [[u8; 3]]>) {
+        self.channel_balance = table;
+    }
+
+    /// Apply the gamma correction table and white balance to the RGB values, and reorder them to match the wiring
+    /// * `channel` - the channel index beginning at 1
+    /// * `color` - RGB values
+    fn correct_color(&self, channel: u8, color: [u8; 3]) -> [u8; 3] {
+        self.correct_color_wide(channel, color.map(|value| (value as u16) << 8))
+            .map(|value| (value >> 8) as u8)
+    }
+
+    /// Apply the gamma correction table to a 16 bit value, interpolating between the table entries
+    /// * `value` - color or luminosity value
+    fn correct_wide(&self, value: u16) -> u16 {
+        match self.gamma {
+            Some(table) => {
+                let (index, fraction) = ((value >> 8) as usize, (value & 0xFF) as i32);
+                let low = table[index] as i32;
+                let high = table[(index + 1).min(255)] as i32;
+                ((low << 8) + (high - low) * fraction) as u16
+            }
+            None => value,
+        }
+    }
+
+    /// Apply the gamma correction table and white balance to 16 bit RGB values, and reorder them to match the wiring
+    /// * `channel` - the channel index beginning at 1
+    /// * `color` - RGB values
+    fn correct_color_wide(&self, channel: u8, color: [u16; 3]) -> [u16; 3] {
+        let channel_balance = self
+            .channel_balance
+            .and_then(|table| table.get((channel as usize).wrapping_sub(1)))
+            .copied()
+            .unwrap_or([0xFF; 3]);
+
+        let mut corrected = [0u16; 3];
+        for (index, value) in corrected.iter_mut().enumerate() {
+            let balance = self.white_balance[index] as u32 * channel_balance[index] as u32 / 255;
+            *value = (self.correct_wide(color[index]) as u32 * balance / 255) as u16;
+        }
+        self.color_order.apply(corrected)
+    }
+
+    /// Scale a 16 bit value by the brightness factor, the ambient light scaling factor and the thermal limit
+    /// * `value` - luminosity value
+    fn scale_wide(&self, value: u16) -> u16 {
+        let scaled = (value as f32 * self.brightness_factor) as u32;
+        (scaled * self.ambient_scale as u32 * self.thermal_limit as u32 / (255 * 100)) as u16
+    }
+
+    /// Set buffered mode. When enabled, `set` only updates an in-RAM shadow of the brightness and color
+    /// registers, and `flush` writes the changed registers using as few transactions as possible.
+    /// NOTE: Flushing relies on the auto-increment feature, which is enabled by default
+    /// * `state` - Buffered mode enable
+    pub fn set_buffered(&mut self, state: bool) {
+        self.buffered = state;
+    }
+
+    /// Set the minimum interval between the frames written by `flush` in buffered mode, so a chatty
+    /// application cannot saturate a bus shared with other peripherals. A flush within the interval of the
+    /// previous frame writes nothing, leaving the changes pending for a later flush
+    /// * `min_interval_ms` - Minimum interval between frames in milliseconds
+    /// * `clock` - Monotonic clock in milliseconds, which may wrap around
+    pub fn set_frame_limit(&mut self, min_interval_ms: u32, clock: fn() -> u32) {
+        self.frame_limit = Some((min_interval_ms, clock));
+    }
+
+    /// Stop throttling the frames written by `flush`
+    pub fn clear_frame_limit(&mut self) {
+        self.frame_limit = None;
+        self.last_frame = None;
+    }
+
+    /// Whether a buffered flush must be skipped as the previous frame was written within the minimum
+    /// interval, otherwise the frame about to be written is recorded
+    fn throttle_frame(&mut self) -> bool {
+        let (interval, clock) = match self.frame_limit {
+            Some(limit) if self.buffered && self.frame.dirty_run().is_some() => limit,
+            _ => return false,
+        };
+        let now = clock();
+        if matches!(self.last_frame, Some(last) if now.wrapping_sub(last) < interval) {
+            return true;
+        }
+        self.last_frame = Some(now);
+        false
+    }
+
+    /// Whether a chip was put in standby with `standby`, for Broadcast whether every chip in the chain was
+    /// * `address` - Address of the LP50xx
+    pub fn is_standby(&self, address: Address) -> bool {
+        let chips = self.chip_mask(address);
+        self.standby_chips & chips == chips
+    }
+
+    /// Helper function to get the bit mask of the chips in the chain reached by an address
+    fn chip_mask(&self, address: Address) -> u8 {
+        match address {
+            Address::Broadcast => (1u16 << self.chain_length).wrapping_sub(1) as u8,
+            Address::Independent(chip) => 1u8.checked_shl(chip as u32).unwrap_or(0),
+        }
+    }
+
+    /// Helper function to forget the register state shadowed by the driver once the chips are reset to
+    /// their defaults
+    fn forget_registers(&mut self) {
+        self.config = DeviceConfig::default();
+        self.frame = FrameBuffer::new();
+        self.brightness_addressing = false;
+        self.standby_chips = 0;
+    }
+
+    /// Set the delays of the enable and reset sequences, the default follows the datasheet
+    /// * `timing` - The delays in microseconds
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    /// Get the delays of the enable and reset sequences
+    pub fn timing(&self) -> Timing {
+        self.timing
+    }
+
+    /// Set the number of steps used by `fade_to`, more steps give a smoother ramp at the cost of more writes.
+    /// The default is 32 steps
+    /// * `steps` - Number of steps, at least 1
+    pub fn set_fade_steps(&mut self, steps: u16) {
+        self.fade_steps = steps.max(1);
+    }
+
+    /// Resolve the 7bit I2C address, applying any address override
+    /// * `addr` - Address of the LP50xx
+    fn get_i2c_address(&self, addr: Address) -> Result<u8, Error> {
+        match (addr, self.base_address, self.broadcast_address) {
+            (Address::Independent(_), Some(base), _) => {
+                let address = addr.into_u8_for_model(self.model)?;
+                Ok(base | (address & 0b11))
+            }
+            (Address::Broadcast, _, Some(broadcast)) => Ok(broadcast),
+            _ => addr.into_u8_for_model(self.model),
+        }
+    }
+
+    /// Update the shadow of the brightness and color registers
+    /// * `addr` - Address of the LP50xx
+    /// * `register` - Address of the first register
+    /// * `values` - The register values
+    fn update_frame(&mut self, addr: Address, register: u8, values: &[u8]) -> Result<(), Error> {
+        let offset = (register - self.model.get_brightness_base()) as usize;
+        self.frame.update(addr, offset, values, self.buffered)
+    }
+}
+
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Read a single register using the configured transport, reading is not possible with the
+    /// asynchronous transfer callback
+    /// * `addr` - Address of the LP50xx
+    /// * `register` - Address of the register to read
+    fn read(&mut self, addr: Address, register: u8) -> Result<u8, Error> {
+        if let Address::Broadcast = addr {
+            return Err(Error::InvalidAddress);
+        }
+
+        let address = self.get_i2c_address(addr)?;
+        let mut buffer = [0u8];
+        self.interface
+            .write_read(address, &[register], &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    transport_methods!(blocking);
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Disabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    disabled_methods!(blocking);
+}
+
+impl<MODE, IFACE, EN, DELAY> LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, Disabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Enable the LP50xx, timed by the delay provider owned by the driver
+    pub fn enable_timed(
+        self,
+    ) -> Result<LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, Enabled>, Error> {
+        let (driver, mut delay) = self.split_delay();
+        let driver = driver.enable(&mut delay)?;
+        Ok(driver.join_delay(delay))
+    }
+}
+
+impl<MODE, IFACE, EN, DELAY> LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Reset the LP50xx, timed by the delay provider owned by the driver
+    pub fn reset_timed(
+        self,
+    ) -> Result<LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, Disabled>, Error> {
+        let (driver, mut delay) = self.split_delay();
+        let driver = driver.reset(&mut delay)?;
+        Ok(driver.join_delay(delay))
+    }
+}
+
+impl<MODE, IFACE, EN, DELAY, STATE> LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, STATE>
+where
+    EN: OutputPin,
+{
+    /// Helper function to take the owned delay provider out of the enable line
+    fn split_delay(self) -> (LP50xx<MODE, IFACE, EN, STATE>, DELAY) {
+        let mut delay = None;
+        let driver = self.convert(|line| {
+            let (pin, owned) = line.into_inner().into_inner();
+            delay = Some(owned);
+            EnableLine::new(pin)
+        });
+        match delay {
+            Some(delay) => (driver, delay),
+            None => unreachable!("the enable line is always converted"),
+        }
+    }
+}
+
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE>
+where
+    EN: OutputPin,
+{
+    /// Helper function to return a delay provider to the enable line
+    fn join_delay<DELAY>(self, delay: DELAY) -> LP50xx<MODE, IFACE, TimedEnable<EN, DELAY>, STATE> {
+        self.convert(|line| EnableLine::new(TimedEnable::new(line.into_inner(), delay)))
+    }
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    enabled_methods!(blocking);
+
+    /// Reset the registers of a single chip without disturbing the other chips on the bus.
+    /// The shared enable line is left untouched, and the chip is enabled again once reset
+    /// * `address` - Address of the LP50xx to reset
+    /// * `delay` - delay provider
+    pub fn reset_chip<DELAY>(&mut self, address: Address, delay: &mut DELAY) -> Result<(), Error>
+    where
+        DELAY: DelayNs,
+    {
+        let reset = self.model.get_reset_register();
+        self.write(address, &[reset, 0xff])?;
+        delay.delay_us(self.timing.reset_us);
+        self.write(address, &[registers::DEVICE_CONFIG0, registers::CHIP_EN])?;
+        self.standby_chips &= !self.chip_mask(address);
+        Ok(())
+    }
+
+    /// Rewrite the configuration and every cached brightness and color register of the chips in the chain,
+    /// after a brown-out or external reset returned the registers to their defaults. Each chip is written
+    /// in two auto-increment transactions, and writes pending a flush in buffered mode are written as well
+    /// NOTE: The bank registers are not cached and must be written again by the application
+    pub fn resync(&mut self) -> Result<(), Error> {
+        for chip in 0..self.chain_length {
+            self.resync_chip(Address::Independent(chip))?;
+        }
+        let len = self.model.get_output_count();
+        self.frame.clean(Address::Broadcast, 0, len);
+        Ok(())
+    }
+
+    /// Read the CHIP_EN bit of every chip in the chain and resync the chips found reset, notifying the
+    /// power loss handler. Returns whether any chip was reset
+    /// NOTE: This relies on a transport supporting reads
+    pub fn check_power_loss(&mut self) -> Result<bool, Error> {
+        let mut reset = false;
+        for chip in 0..self.chain_length {
+            let address = Address::Independent(chip);
+            if self.is_standby(address) {
+                continue;
+            }
+            if self.read(address, registers::DEVICE_CONFIG0)? & registers::CHIP_EN == 0 {
+                self.resync_chip(address)?;
+                if let Some(handler) = self.power_loss {
+                    handler(address);
+                }
+                reset = true;
+            }
+        }
+        Ok(reset)
+    }
+
+    /// Rewrite the configuration and cached brightness and color registers of a single chip, a chip in
+    /// standby is left in standby
+    /// * `address` - Independent address of the LP50xx
+    fn resync_chip(&mut self, address: Address) -> Result<(), Error> {
+        let base = self.model.get_brightness_base();
+        let len = self.model.get_output_count();
+        let chip_en = if self.is_standby(address) {
+            0
+        } else {
+            registers::CHIP_EN
+        };
+        self.write(
+            address,
+            &[registers::DEVICE_CONFIG0, chip_en, self.config.into_u8()],
+        )?;
+
+        let mut payload = [0u8; 1 + MAX_REGISTERS];
+        payload[0] = base;
+        payload[1..=len].copy_from_slice(self.frame.get(address, 0, len)?);
+        self.write(address, &payload[..=len])?;
+        self.frame.clean(address, 0, len);
+        Ok(())
+    }
+
+    /// Recover an I2C bus held by a chip stuck mid-transfer, by clocking SCL nine times so the chip releases
+    /// SDA, and then re-enabling and resyncing the chips. The SCL pin must be released by the I2C peripheral
+    /// and driven as an open-drain output for the duration of the recovery
+    /// * `scl` - The SCL line as a GPIO output
+    /// * `delay` - delay provider
+    pub fn recover_bus<SCL, DELAY>(&mut self, scl: &mut SCL, delay: &mut DELAY) -> Result<(), Error>
+    where
+        SCL: OutputPin,
+        DELAY: DelayNs,
+    {
+        // Nine clock pulses at 100kHz
+        for _ in 0..9 {
+            scl.set_low().map_err(|_| Error::BusRecovery)?;
+            delay.delay_us(5);
+            scl.set_high().map_err(|_| Error::BusRecovery)?;
+            delay.delay_us(5);
+        }
+        self.reenable(delay)
+    }
+
+    /// Recover an I2C bus held by a chip stuck mid-transfer using the recovery of the HAL, such as an I2C
+    /// peripheral able to issue the clock pulses itself, and then re-enable and resync the chips
+    /// * `recover` - Hook performing the recovery of the bus
+    /// * `delay` - delay provider
+    pub fn recover_bus_with<F, DELAY>(&mut self, recover: F, delay: &mut DELAY) -> Result<(), Error>
+    where
+        F: FnOnce() -> Result<(), Error>,
+        DELAY: DelayNs,
+    {
+        recover()?;
+        self.reenable(delay)
+    }
+
+    /// Pulse the enable line and resync the chips, restoring the registers lost in shutdown
+    /// * `delay` - delay provider
+    fn reenable<DELAY: DelayNs>(&mut self, delay: &mut DELAY) -> Result<(), Error> {
+        self.enable.set_low().map_err(|_| Error::EnableLine)?;
+        delay.delay_us(self.timing.enable_low_us);
+        self.enable.set_high().map_err(|_| Error::EnableLine)?;
+        delay.delay_us(self.timing.startup_us);
+        self.resync()
+    }
+
+    /// Set the brightness shared by all channels assigned to the bank, dimming the whole bank with a single write
+    /// * `brightness` - The bank brightness value
+    pub fn set_bank_brightness(&mut self, brightness: u8) -> Result<(), Error> {
+        let bank_brightness = self.model.get_bank_brightness_register();
+        self.write(self.active_address, &[bank_brightness, brightness])
+    }
+
+    /// Write LED_CONFIG0, and LED_CONFIG1 on the LP5030 and LP5036
+    /// * `address` - Address of the LP50xx
+    /// * `config` - The channels in the bank
+    fn write_led_config(&mut self, address: Address, config: LedConfig) -> Result<(), Error> {
+        let [led_config0, led_config1] = config.into_registers();
+        self.write(address, &[registers::LED_CONFIG0, led_config0])?;
+        // LED_CONFIG1 holds the bank bits of channels 9 to 12
+        if self.model.get_channel_count() > 8 {
+            self.write(address, &[registers::LED_CONFIG1, led_config1])?;
+        }
+        Ok(())
+    }
+
+    /// The last written configuration, which the `set_*` configuration methods modify
+    pub fn config(&self) -> DeviceConfig {
+        self.config
+    }
+
+    /// Enable or disable the logarithmic scale dimming curve, retaining the rest of the last written configuration
+    /// * `enable` - Logarithmic scale dimming curve
+    pub fn set_log_scale(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.log_scale(enable))
+    }
+
+    /// Enable or disable the automatic power-saving mode, retaining the rest of the last written configuration
+    /// * `enable` - Automatic power-saving mode enabled
+    pub fn set_power_save(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.power_save(enable))
+    }
+
+    /// Enable or disable auto-increment, retaining the rest of the last written configuration
+    /// NOTE: Buffered flushes and frame writes rely on auto-increment
+    /// * `enable` - Auto-increment enabled
+    pub fn set_auto_increment(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.auto_incr(enable))
+    }
+
+    /// Enable or disable PWM dithering, retaining the rest of the last written configuration
+    /// * `enable` - PWM dithering mode enabled
+    pub fn set_pwm_dithering(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.pwm_dithering(enable))
+    }
+
+    /// Set the output maximum current option, retaining the rest of the last written configuration
+    /// * `enable` - Output maximum current enable: IMAX = 35 mA, disable: IMAX = 25.5mA
+    pub fn set_max_current(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.max_current_option(enable))
+    }
+
+    /// Shut down or restore all LEDs by flipping only the LED_Global_Off bit, retaining the rest of
+    /// the last written configuration and all color and brightness registers
+    /// * `enable` - Shut down all LEDs when enabled
+    pub fn set_global_off(&mut self, enable: bool) -> Result<(), Error> {
+        self.configure_with(self.config.global_off(enable))
+    }
+
+    /// Blank the whole fixture instantly, use `set_global_off(false)` to restore it
+    pub fn blackout(&mut self) -> Result<(), Error> {
+        self.set_global_off(true)
+    }
+
+    /// Turn every output off by writing zero to all OUTx_COLOR registers in a single transaction, or
+    /// with brightness addressing to all LEDx_BRIGHTNESS registers, leaving the colors fully on.
+    /// With continuous addressing every chip is cleared at once using Broadcast, otherwise the
+    /// outputs of the active address are cleared
+    pub fn clear(&mut self) -> Result<(), Error> {
+        let address = if self.continuous_addressing {
+            Address::Broadcast
+        } else {
+            self.active_address
+        };
+        let (base, leds) = self.get_led_registers();
+        self.write_outputs(address, base, &[0; MAX_REGISTERS][..leds as usize])
+    }
+
+    /// Write the OUTx_COLOR registers of the active address in a single auto-increment transaction,
+    /// beginning at OUT0_COLOR
+    /// NOTE: With brightness addressing the LEDs only light while their OUTx_COLOR registers are 0xFF
+    /// * `colors` - The color register values, at most one per pin of the model
+    pub fn write_frame(&mut self, colors: &[u8]) -> Result<(), Error> {
+        if colors.len() > self.model.get_pin_count() as usize {
+            return Err(Error::InvalidLed);
+        }
+
+        let color_base = self.model.get_color_base();
+        self.write_outputs(self.active_address, color_base, colors)
+    }
+
+    /// Write every LEDx_BRIGHTNESS register followed by the OUTx_COLOR registers of the active address
+    /// in a single auto-increment transaction, beginning at LED0_BRIGHTNESS. The reserved LEDx_BRIGHTNESS
+    /// registers between the two on the LP5009, LP5018 and LP5030 are written with their cached values
+    /// NOTE: With brightness addressing the LEDs only light while their OUTx_COLOR registers are 0xFF
+    /// * `brightness` - The brightness register values, exactly one per RGB channel of the model
+    /// * `colors` - The color register values, at most one per pin of the model
+    pub fn write_frame_with_brightness(
+        &mut self,
+        brightness: &[u8],
+        colors: &[u8],
+    ) -> Result<(), Error> {
+        let channels = self.model.get_channel_count() as usize;
+        if brightness.len() != channels {
+            return Err(Error::InvalidChannel);
+        }
+        if colors.len() > self.model.get_pin_count() as usize {
+            return Err(Error::InvalidLed);
+        }
+
+        let color_offset = self.model.get_color_offset();
+        let mut values = [0u8; MAX_REGISTERS];
+        values[..color_offset].copy_from_slice(self.frame.get(
+            self.active_address,
+            0,
+            color_offset,
+        )?);
+        values[..channels].copy_from_slice(brightness);
+        values[color_offset..color_offset + colors.len()].copy_from_slice(colors);
+
+        let brightness_base = self.model.get_brightness_base();
+        self.write_outputs(
+            self.active_address,
+            brightness_base,
+            &values[..color_offset + colors.len()],
+        )
+    }
+
+    /// Read a register from the active address using the blocking I2C interface.
+    /// NOTE: Broadcast cannot be read from, an independent active address must be set
+    /// * `register` - Address of the register to read
+    pub fn read_register(&mut self, register: u8) -> Result<u8, Error> {
+        let address = self.active_address;
+        self.read(address, register)
+    }
+
+    /// Write a register of the active address, exposing the full register map for chip features
+    /// not covered by the typed API
+    /// * `register` - Address of the register
+    /// * `value` - The register value
+    pub fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error> {
+        self.write_registers(register, &[value])
+    }
+
+    /// Write consecutive registers of the active address in a single auto-increment transaction.
+    /// The shadow of the brightness and color registers is kept up to date, as are the configuration
+    /// and the standby state written to DEVICE_CONFIG1 and DEVICE_CONFIG0
+    /// NOTE: Writing consecutive registers relies on the auto-increment feature, which is enabled by default
+    /// * `register` - Address of the first register
+    /// * `values` - The register values
+    pub fn write_registers(&mut self, register: u8, values: &[u8]) -> Result<(), Error> {
+        if register as usize + values.len() > self.model.get_register_count() {
+            return Err(Error::InvalidRegister);
+        }
+
+        let mut payload = [0u8; 1 + MAX_REGISTER_COUNT];
+        payload[0] = register;
+        payload[1..=values.len()].copy_from_slice(values);
+        self.write(self.active_address, &payload[..=values.len()])?;
+
+        // Registers written from the brightness and color registers
+        let base = self.model.get_brightness_base();
+        let outputs = self.model.get_color_base() + self.model.get_pin_count();
+        let (start, end) = (
+            register.max(base),
+            (register + values.len() as u8).min(outputs),
+        );
+        if start < end {
+            let written = &values[(start - register) as usize..(end - register) as usize];
+            self.frame
+                .update(self.active_address, (start - base) as usize, written, false)?;
+        }
+
+        // Keep the configuration shadow up to date, so the `set_*` configuration methods retain the write
+        if let Some(config) =
+            values.get((registers::DEVICE_CONFIG1 as usize).wrapping_sub(register as usize))
+        {
+            self.config = DeviceConfig::from_u8(*config);
+        }
+        // and the standby mask follows the CHIP_EN bit, so `check_power_loss` and `resync` retain it
+        if let Some(config) =
+            values.get((registers::DEVICE_CONFIG0 as usize).wrapping_sub(register as usize))
+        {
+            let chips = self.chip_mask(self.active_address);
+            if config & registers::CHIP_EN == 0 {
+                self.standby_chips |= chips;
+            } else {
+                self.standby_chips &= !chips;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the DEVICE_CONFIG1 register of the active address, see `configure` for the meaning of each bit
+    pub fn read_device_config(&mut self) -> Result<u8, Error> {
+        self.read_register(registers::DEVICE_CONFIG1)
+    }
+
+    /// Read DEVICE_CONFIG1 from the chip, modify it and write it back. Unlike the `set_*` configuration
+    /// methods, which modify the last written configuration, this retains settings written by other bus masters
+    /// or before a reset of the microcontroller
+    /// NOTE: Broadcast cannot be read from, an independent active address must be set
+    /// * `modify` - Modifies the configuration read from the chip
+    pub fn modify_config<F>(&mut self, modify: F) -> Result<(), Error>
+    where
+        F: FnOnce(DeviceConfig) -> DeviceConfig,
+    {
+        let config = DeviceConfig::from_u8(self.read_device_config()?);
+        self.configure_with(modify(config))
+    }
+
+    /// Read the whole register file of a chip, beginning at DEVICE_CONFIG0, in a single auto-increment
+    /// transaction. Useful to log the chip state when debugging. Returns the number of registers read,
+    /// which is the smaller of the buffer length and the register count of the model
+    /// NOTE: Reading consecutive registers relies on the auto-increment feature, which is enabled by default
+    /// * `address` - Independent address of the LP50xx
+    /// * `buffer` - The buffer the register values are placed in
+    pub fn dump_registers(&mut self, address: Address, buffer: &mut [u8]) -> Result<usize, Error> {
+        if let Address::Broadcast = address {
+            return Err(Error::InvalidAddress);
+        }
+
+        let count = buffer.len().min(self.model.get_register_count());
+        let address = self.get_i2c_address(address)?;
+        self.interface
+            .write_read(address, &[registers::DEVICE_CONFIG0], &mut buffer[..count])?;
+        Ok(count)
+    }
+
+    /// Read back the OUTx_COLOR register of an individual LED, following the same addressing as
+    /// writes in monochromatic mode
+    /// * `led` - the LED index beginning at 1
+    pub fn read_out_color(&mut self, led: u16) -> Result<u8, Error> {
+        let (address, register) = self.get_led_target(led)?;
+        self.read(address, register)
+    }
+
+    /// Check whether a chip responds by reading its DEVICE_CONFIG0 register, which has no side effects.
+    /// Useful to detect missing or unresponsive LED boards at startup. Broadcast cannot be read from,
+    /// so the register address is written instead, which any chip on the bus acknowledges
+    /// * `address` - Address of the LP50xx
+    pub fn probe(&mut self, address: Address) -> Result<bool, Error> {
+        let result = match address {
+            Address::Broadcast => self.write_transaction(address, &[registers::DEVICE_CONFIG0]),
+            Address::Independent(_) => self.read(address, registers::DEVICE_CONFIG0).map(|_| ()),
+        };
+        match result {
+            Ok(()) => Ok(true),
+            Err(Error::CommError(embedded_hal::i2c::ErrorKind::NoAcknowledge(_))) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Probe the four independent addresses, reporting which chips respond.
+    /// Useful in factory bring-up and to configure the chain length
+    pub fn scan(&mut self) -> Result<[bool; MAX_CHIPS], Error> {
+        let mut found = [false; MAX_CHIPS];
+        for (address, found) in (0..).zip(found.iter_mut()) {
+            *found = self.probe(Address::Independent(address))?;
+        }
+        Ok(found)
+    }
+
+    /// Detect the number of chips in the daisy-chain by probing the independent addresses from 0b00,
+    /// and store it as the chain length used by continuous addressing. LEDs beyond the detected chain
+    /// return `Error::LedOutOfRange`. Returns `Error::InvalidAddress`, leaving the chain length unchanged,
+    /// when no chip responds at 0b00. Requires a transport which supports reading
+    pub fn detect_chain_length(&mut self) -> Result<u8, Error> {
+        let found = self.scan()?;
+        let length = found.iter().take_while(|found| **found).count() as u8;
+        if length == 0 {
+            return Err(Error::InvalidAddress);
+        }
+        self.set_chain_length(length)?;
+        Ok(length)
+    }
+}
+
+// Color Mode
+
+impl<IFACE, EN> LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    color_methods!(blocking);
+
+    /// Set the brightness and RGB values of any number of channels, in any order. The registers are
+    /// gathered in the shadow and written per chip, each run of consecutive registers in a single
+    /// auto-increment transaction, deferred until the next flush in buffered mode
+    /// * `channels` - the channel numbers with their brightness and RGB values
+    pub fn set_many<I>(&mut self, channels: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (u8, (u8, [u8; 3]))>,
+    {
+        let base = self.model.get_brightness_base();
+        let address = self.active_address;
+        let result = channels
+            .into_iter()
+            .try_for_each(|(channel, (brightness, color))| {
+                let (bright_addr, color_addr) = get_channel_registers(channel, self.model)?;
+                let brightness = self.scale(brightness);
+                let color = self.correct_color(channel, color);
+                self.frame
+                    .update(address, (bright_addr - base) as usize, &[brightness], true)?;
+                self.frame
+                    .update(address, (color_addr - base) as usize, &color, true)
+            });
+
+        if !self.buffered {
+            self.flush()?;
+        }
+        result
+    }
+
+    /// Set the brightness and RGB values of every channel in a single transaction to the active address,
+    /// which is Broadcast by default so every chip on the bus is set at once
+    /// * `brightness` - the channel brightness
+    /// * `color` - the channel RGB values
+    pub fn fill_color(&mut self, brightness: u8, color: [u8; 3]) -> Result<(), Error> {
+        let mut values = [0u8; MAX_REGISTERS];
+        for channel in 1..=self.model.get_channel_count() {
+            self.encode_channel(&mut values, channel, brightness, color);
+        }
+
+        let brightness_base = self.model.get_brightness_base();
+        let len = self.model.get_output_count();
+        self.write_outputs(self.active_address, brightness_base, &values[..len])
+    }
+
+    /// Set the brightness and RGB values of a contiguous range of channels, batching the registers
+    /// into one brightness and one color transaction
+    /// * `channels` - the channel indices beginning at 1, such as `2..=4`
+    /// * `brightness` - the channel brightness
+    /// * `color` - the channel RGB values
+    pub fn set_range_color(
+        &mut self,
+        channels: RangeInclusive<impl ChannelIndex>,
+        (brightness, [r, g, b]): (u8, [u8; 3]),
+    ) -> Result<(), Error> {
+        let (start, end) = channels.into_inner();
+        let (start, end) = (start.channel_index(), end.channel_index());
+        if start > end {
+            return Ok(());
+        }
+        get_channel_registers(end, self.model)?;
+        let (bright_addr, color_addr) = get_channel_registers(start, self.model)?;
+        let count = (end - start + 1) as usize;
+
+        let brightness = [self.scale(brightness); MAX_REGISTERS];
+        let mut colors = [0u8; MAX_REGISTERS];
+        for (channel, rgb) in (start..).zip(colors[..count * 3].chunks_exact_mut(3)) {
+            rgb.copy_from_slice(&self.correct_color(channel, [r, g, b]));
+        }
+
+        self.write_outputs(self.active_address, bright_addr, &brightness[..count])?;
+        self.write_outputs(self.active_address, color_addr, &colors[..count * 3])
+    }
+
+    /// Set the channel color from HSV values, the channel brightness is set to full scale
+    /// * `channel` - the channel index beginning at 1
+    /// * `hue` - Hue in degrees, values of 360 and above wrap around
+    /// * `saturation` - Saturation, 0 being grey and 255 being fully saturated
+    /// * `value` - Value, 0 being black and 255 being full intensity
+    pub fn set_hsv(
+        &mut self,
+        channel: impl ChannelIndex,
+        hue: u16,
+        saturation: u8,
+        value: u8,
+    ) -> Result<(), Error> {
+        self.set(channel, (0xFF, color::hsv_to_rgb(hue, saturation, value)))
+    }
+
+    /// Set the channel brightness and color from an `RGB8`
+    /// * `channel` - the channel index beginning at 1
+    /// * `brightness` - the channel brightness
+    /// * `color` - the channel color
+    #[cfg(feature = "rgb")]
+    pub fn set_rgb8(
+        &mut self,
+        channel: impl ChannelIndex,
+        brightness: u8,
+        color: color::RGB8,
+    ) -> Result<(), Error> {
+        self.set(channel, (brightness, color.into()))
+    }
+
+    /// Assign RGB channels to the bank, channels in the bank follow the bank brightness and color
+    /// registers instead of their own. Writes LED_CONFIG0 (and LED_CONFIG1 on the LP5030 and LP5036)
+    /// * `led_mask` - Bit mask of the channels in the bank, bit 0 being channel 1
+    pub fn assign_to_bank(&mut self, led_mask: u16) -> Result<(), Error> {
+        self.assign_to_bank_with(LedConfig::from_u16(led_mask))
+    }
+
+    /// Assign RGB channels to the bank using a typed `LedConfig`
+    /// * `config` - The channels in the bank, encoded into LED_CONFIG0 and LED_CONFIG1
+    pub fn assign_to_bank_with(&mut self, config: LedConfig) -> Result<(), Error> {
+        if config.into_u16() >> self.model.get_channel_count() != 0 {
+            return Err(Error::InvalidChannel);
+        }
+
+        self.write_led_config(self.active_address, config)
+    }
+
+    /// Set the RGB values shared by all channels assigned to the bank
+    /// * `color` - The BANK_A, BANK_B and BANK_C color values
+    pub fn set_bank_color(&mut self, [r, g, b]: [u8; 3]) -> Result<(), Error> {
+        let bank_color = self.model.get_bank_color_base();
+        let [a, b, c] = self.color_order.apply([r, g, b]);
+        self.write(self.active_address, &[bank_color, a, b, c])
+    }
+
+    /// Set the color shared by all channels assigned to the bank from an `RGB8`
+    /// * `color` - The bank color
+    #[cfg(feature = "rgb")]
+    pub fn set_bank_color_rgb8(&mut self, color: color::RGB8) -> Result<(), Error> {
+        self.set_bank_color(color.into())
+    }
+}
+
+// Monochromatic Mode
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    monochromatic_methods!(blocking);
+
+    /// Set brightness addressing. When enabled each LED is driven by the LEDx_BRIGHTNESS register of an RGB
+    /// channel instead of an OUTx_COLOR register, so the LEDs follow the logarithmic dimming curve and the
+    /// three pins of a channel form a single LED. The color registers are set to full scale and the
+    /// brightness registers to off, or the other way around when disabled, so every LED is turned off.
+    /// With continuous addressing every chip is written at once using Broadcast
+    /// * `state` - Brightness addressing enable
+    pub fn set_brightness_addressing(&mut self, state: bool) -> Result<(), Error> {
+        let address = if self.continuous_addressing {
+            Address::Broadcast
+        } else {
+            self.active_address
+        };
+        let channels = self.model.get_channel_count() as usize;
+        let (brightness, color) = if state { (0x00, 0xFF) } else { (0xFF, 0x00) };
+
+        let brightness_base = self.model.get_brightness_base();
+        self.write_outputs(
+            address,
+            brightness_base,
+            &[brightness; MAX_REGISTERS][..channels],
+        )?;
+        let color_base = self.model.get_color_base();
+        self.write_outputs(address, color_base, &[color; MAX_REGISTERS][..channels * 3])?;
+        self.brightness_addressing = state;
+        Ok(())
+    }
+
+    /// Get whether brightness addressing is enabled
+    pub fn brightness_addressing(&self) -> bool {
+        self.brightness_addressing
+    }
+
+    /// Set any number of LEDs, in any order. The registers are gathered in the shadow and written per chip,
+    /// each run of consecutive registers in a single auto-increment transaction, deferred until the next
+    /// flush in buffered mode
+    /// * `leds` - the LED indices beginning at 1 with their luminosity values
+    pub fn set_many<I>(&mut self, leds: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (u16, u8)>,
+    {
+        let base = self.model.get_brightness_base();
+        let result = leds.into_iter().try_for_each(|(led, value)| {
+            let (address, register, value) = self.get_led_write(led, value)?;
+            self.frame
+                .update(address, (register - base) as usize, &[value], true)
+        });
+
+        if !self.buffered {
+            self.flush()?;
+        }
+        result
+    }
+
+    /// Set a contiguous range of LEDs to the same value, batching consecutive registers into a single
+    /// transaction per chip. With continuous addressing the range may span several chips
+    /// * `leds` - the LED indices beginning at 1, such as `5..=20`
+    /// * `value` - luminosity value
+    pub fn set_range(&mut self, leds: RangeInclusive<u16>, value: u8) -> Result<(), Error> {
+        let (mut led, end) = leds.into_inner();
+        let (led_base, leds) = self.get_led_registers();
+        let mut values = [0u8; MAX_REGISTERS];
+
+        while led <= end {
+            let (address, register) = self.get_led_target(led)?;
+            // LEDs remaining on this chip from the current register onwards
+            let remaining = (leds - (register - led_base)) as u16;
+            let count = remaining.min(end - led + 1);
+
+            let values = &mut values[..count as usize];
+            self.encode_leds(address, register, led, values, |_| value)?;
+            self.write_outputs(address, register, values)?;
+            led += count;
+        }
+        Ok(())
+    }
+
+    /// Set consecutive LEDs beginning at LED 1 from a slice of luminosity values, writing each chip in a
+    /// single transaction. With continuous addressing the values continue across the chips in the chain
+    /// * `values` - luminosity value of each LED
+    pub fn set_all(&mut self, values: &[u8]) -> Result<(), Error> {
+        // Check the last LED before writing any chip
+        if values.len() > u16::MAX as usize {
+            return Err(Error::LedOutOfRange);
+        }
+        if !values.is_empty() {
+            self.get_led_target(values.len() as u16)?;
+        }
+
+        let (_, leds) = self.get_led_registers();
+        let leds = leds as usize;
+        let mut registers = [0u8; MAX_REGISTERS];
+        for (first, values) in (1..).step_by(leds).zip(values.chunks(leds)) {
+            let (address, register) = self.get_led_target(first)?;
+            let registers = &mut registers[..values.len()];
+            self.encode_leds(address, register, first, registers, |led| {
+                values[(led - first) as usize]
+            })?;
+            self.write_outputs(address, register, registers)?;
+        }
+        Ok(())
+    }
+
+    /// Assign LEDs to the bank, replacing the previous assignment. LEDs in the bank follow BANK_BRIGHTNESS
+    /// and the bank color registers instead of their own, so the group is dimmed with a single
+    /// `set_bank_brightness` write. The bank is assigned by RGB channel, so each LED brings the other two
+    /// LEDs of its channel: OUT0 to OUT2 form channel 1, OUT3 to OUT5 channel 2 and so on. With brightness
+    /// addressing every LED is a channel of its own. With continuous addressing the bank of every chip in
+    /// the chain is written
+    /// * `leds` - the LED indices beginning at 1
+    pub fn assign_leds_to_bank(&mut self, leds: &[u16]) -> Result<(), Error> {
+        let (led_base, _) = self.get_led_registers();
+        let pins_per_led = if self.brightness_addressing { 1 } else { 3 };
+        let mut configs = [LedConfig::default(); MAX_CHIPS];
+        for led in leds {
+            let (address, register) = self.get_led_target(*led)?;
+            let chip = match address {
+                Address::Independent(chip) if self.continuous_addressing => chip as usize,
+                _ => 0,
+            };
+            let channel = (register - led_base) / pins_per_led + 1;
+            configs[chip] = configs[chip].bank(channel, true);
+        }
+
+        if !self.continuous_addressing {
+            return self.write_led_config(self.active_address, configs[0]);
+        }
+        for (chip, config) in (0..self.chain_length).zip(configs) {
+            self.write_led_config(Address::Independent(chip), config)?;
+        }
+        Ok(())
+    }
+
+    /// Set the luminosity of every LED in the bank, BANK_A_COLOR, BANK_B_COLOR and BANK_C_COLOR drive the
+    /// first, second and third LED of each channel in the bank. With brightness addressing BANK_BRIGHTNESS
+    /// drives the LEDs in the bank instead, with the bank colors at full scale
+    /// * `value` - luminosity value
+    pub fn set_bank(&mut self, value: u8) -> Result<(), Error> {
+        let value = self.scale(self.correct(value));
+        if self.brightness_addressing {
+            let bank_brightness = self.model.get_bank_brightness_register();
+            return self.write(
+                self.active_address,
+                &[bank_brightness, value, 0xFF, 0xFF, 0xFF],
+            );
+        }
+        let bank_color = self.model.get_bank_color_base();
+        self.write(self.active_address, &[bank_color, value, value, value])
+    }
+
+    /// Set every LED to the same value in a single transaction. With continuous addressing every chip
+    /// is set at once using Broadcast, otherwise the LEDs of the active address are set. When LEDs are
+    /// masked each chip is written separately, skipping the masked LEDs
+    /// * `value` - luminosity value
+    pub fn fill(&mut self, value: u8) -> Result<(), Error> {
+        if self.led_mask.iter().any(|word| *word != 0) {
+            return self.write_leds(|_| value);
+        }
+        let address = if self.continuous_addressing {
+            Address::Broadcast
+        } else {
+            self.active_address
+        };
+        let (led_base, leds) = self.get_led_registers();
+        let values = [self.scale(self.correct(value)); MAX_REGISTERS];
+        self.write_outputs(address, led_base, &values[..leds as usize])
+    }
+
+    /// Set every LED of the chain, writing each chip in a single transaction
+    /// * `value` - luminosity value of the LED index beginning at 1
+    fn write_leds<F: Fn(u16) -> u8>(&mut self, value: F) -> Result<(), Error> {
+        let (_, leds) = self.get_led_registers();
+        let leds = leds as usize;
+        let mut values = [0u8; MAX_REGISTERS];
+
+        for first in (1..=self.led_count()).step_by(leds) {
+            let (address, register) = self.get_led_target(first)?;
+            self.encode_leds(address, register, first, &mut values[..leds], &value)?;
+            self.write_outputs(address, register, &values[..leds])?;
+        }
+        Ok(())
+    }
+}
+
+impl<IFACE, EN, STATE> LP50xx<ColorMode, IFACE, EN, STATE> {
+    /// Validate a channel index against the model
+    /// * `index` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+    pub fn channel(&self, index: u8) -> Result<Channel, Error> {
+        Channel::new(index, self.model)
+    }
+
+    /// Encode the brightness and color registers of a chip for a bulk color write, the channels not
+    /// covered by the values retain their cached registers
+    /// * `chip` - the index of the chip in the chain, or of the chunk of values
+    /// * `values` - the brightness and RGB values of each channel of the chip beginning at channel 1
+    fn encode_colors(
+        &self,
+        chip: u8,
+        values: &[(u8, [u8; 3])],
+    ) -> Result<(Address, [u8; MAX_REGISTERS]), Error> {
+        let address = if self.continuous_addressing {
+            Address::Independent(chip)
+        } else {
+            self.active_address
+        };
+
+        let len = self.model.get_output_count();
+        let mut registers = [0u8; MAX_REGISTERS];
+        registers[..len].copy_from_slice(self.frame.get(address, 0, len)?);
+        for (channel, (brightness, color)) in (1..).zip(values) {
+            self.encode_channel(&mut registers, channel, *brightness, *color);
+        }
+        Ok((address, registers))
+    }
+
+    /// Encode the scaled brightness and corrected color of a channel into a block of registers beginning
+    /// at LED0_BRIGHTNESS, the color registers following any reserved LEDx_BRIGHTNESS registers
+    /// * `registers` - the register block
+    /// * `channel` - the channel index beginning at 1
+    /// * `brightness` - the channel brightness
+    /// * `color` - the channel RGB values
+    pub(crate) fn encode_channel(
+        &self,
+        registers: &mut [u8; MAX_REGISTERS],
+        channel: u8,
+        brightness: u8,
+        color: [u8; 3],
+    ) {
+        let index = channel as usize - 1;
+        let color_index = self.model.get_color_offset() + index * 3;
+        registers[index] = self.scale(brightness);
+        registers[color_index..color_index + 3]
+            .copy_from_slice(&self.correct_color(channel, color));
+    }
+}
+
+impl<IFACE, EN, STATE> LP50xx<MonochromaticMode, IFACE, EN, STATE> {
+    /// Resolve the chip address, register and scaled value for a monochromatic LED write
+    /// * `led` - the LED index beginning at 1
+    /// * `value` - luminosity value
+    fn get_led_write(&self, led: u16, value: u8) -> Result<(Address, u8, u8), Error> {
+        let (address, register) = self.get_led_output(led)?;
+        Ok((address, register, self.scale(self.correct(value))))
+    }
+
+    /// Resolve the chip address and register of an individual LED which may be set
+    /// * `led` - the LED index beginning at 1
+    fn get_led_output(&self, led: u16) -> Result<(Address, u8), Error> {
+        let target = self.get_led_target(led)?;
+        if self.is_led_masked(led) {
+            return Err(Error::LedMasked);
+        }
+        Ok(target)
+    }
+
+    /// Encode the registers of consecutive LEDs of a chip, masked LEDs retain their cached registers
+    /// * `address` - Address of the LP50xx
+    /// * `register` - Register of the first LED
+    /// * `first` - the index of the first LED beginning at 1
+    /// * `registers` - the encoded registers, one for each LED
+    /// * `value` - luminosity value of the LED index
+    fn encode_leds<F: Fn(u16) -> u8>(
+        &self,
+        address: Address,
+        register: u8,
+        first: u16,
+        registers: &mut [u8],
+        value: F,
+    ) -> Result<(), Error> {
+        let offset = (register - self.model.get_brightness_base()) as usize;
+        let cached = self.frame.get(address, offset, registers.len())?;
+        for ((led, register), cached) in (first..).zip(registers.iter_mut()).zip(cached) {
+            *register = if self.is_led_masked(led) {
+                *cached
+            } else {
+                self.scale(self.correct(value(led)))
+            };
+        }
+        Ok(())
+    }
+
+    /// Mask LEDs which are never lit, such as unpopulated footprints on a shared board, replacing the
+    /// previous mask. Fills and effects skip the masked LEDs, which retain their registers, and setting a
+    /// masked LED returns `Error::LedMasked`
+    /// * `leds` - the LED indices beginning at 1
+    pub fn set_led_mask(&mut self, leds: &[u16]) -> Result<(), Error> {
+        let mut mask = [0u64; LED_MASK_WORDS];
+        for led in leds {
+            let index = (*led as usize).checked_sub(1).ok_or(Error::InvalidLed)?;
+            if index >= LED_MASK_WORDS * 64 {
+                return Err(Error::LedOutOfRange);
+            }
+            mask[index / 64] |= 1 << (index % 64);
+        }
+        self.led_mask = mask;
+        Ok(())
+    }
+
+    /// Get whether an LED is masked
+    /// * `led` - the LED index beginning at 1
+    pub fn is_led_masked(&self, led: u16) -> bool {
+        match (led as usize).checked_sub(1) {
+            Some(index) if index < LED_MASK_WORDS * 64 => {
+                self.led_mask[index / 64] & (1 << (index % 64)) != 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of LEDs addressable, every LED of the chain with continuous addressing
+    /// or the LEDs of the active address otherwise
+    fn led_count(&self) -> u16 {
+        self.num_leds()
+    }
+
+    /// Validate an LED index against the model and, with continuous addressing, the chain length
+    /// * `index` - the LED index beginning at 1
+    pub fn led(&self, index: u16) -> Result<Led, Error> {
+        let chips = if self.continuous_addressing {
+            self.chain_length
+        } else {
+            1
+        };
+        let led = Led::new(index, self.model, chips)?;
+        if index > self.num_leds() {
+            return Err(Error::LedOutOfRange);
+        }
+        Ok(led)
+    }
+}
+
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE> {
+    /// Get the first register driving the individual LEDs and the number of LEDs of each chip, the OUTx_COLOR
+    /// register of every pin or, with brightness addressing, the LEDx_BRIGHTNESS register of every channel
+    fn get_led_registers(&self) -> (u8, u8) {
+        if self.brightness_addressing {
+            (
+                self.model.get_brightness_base(),
+                self.model.get_channel_count(),
+            )
+        } else {
+            (self.model.get_color_base(), self.model.get_pin_count())
+        }
+    }
+
+    /// Resolve the chip address and register of an individual LED
+    /// * `led` - the LED index beginning at 1
+    fn get_led_target(&self, led: u16) -> Result<(Address, u8), Error> {
+        let (led_base_address, led_count) = self.get_led_registers();
+        let led_count = led_count as u16;
+        if led == 0 {
+            return Err(Error::InvalidLed);
+        }
+        if !self.continuous_addressing && led > led_count {
+            return Err(Error::InvalidLed);
+        }
+
+        let (address, pin_offset) = if self.continuous_addressing {
+            let addr_offset = get_led_address_offset(led, led_count);
+            if addr_offset >= self.chain_length as u16 {
+                return Err(Error::LedOutOfRange);
+            }
+            let addr = Address::Independent(addr_offset as u8);
+            let pin_offset = led - (addr_offset * led_count);
+            (addr, pin_offset)
+        } else {
+            (self.active_address, led)
+        };
+
+        Ok((address, led_base_address + (pin_offset - 1) as u8))
+    }
+}
+
+/// Get the brightness and color register addresses for the given RGB channel
+/// * `channel` - the channel index beginning at 1
+/// * `model` - Model number of the LP50xx
+fn get_channel_registers(channel: u8, model: Model) -> Result<(u8, u8), Error> {
+    if channel < 1 || channel > model.get_channel_count() {
+        return Err(Error::InvalidChannel);
     }
 
-    return 0x02;
+    let channel = channel - 1;
+    Ok((
+        model.get_brightness_base() + channel,
+        model.get_color_base() + channel * 3,
+    ))
+}
+
+/// Get the led offset address for the given led index and the number of LEDs of each chip
+/// * `led_index` - the LED index beginning at 1
+/// * `led_count` - Number of LEDs of each chip
+fn get_led_address_offset(led_index: u16, led_count: u16) -> u16 {
+    (led_index - 1) / led_count
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{Error, Lp50xxInterface};
+
+    use super::NoEnable as Pin;
+
+    /// Delay provider stub
+    struct Delay;
+
+    impl embedded_hal::delay::DelayNs for Delay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    /// Transport recording the last write
+    struct Recorder {
+        address: u8,
+        data: [u8; 1 + super::MAX_REGISTERS],
+        len: usize,
+    }
+
+    impl Default for Recorder {
+        fn default() -> Self {
+            Self {
+                address: 0,
+                data: [0; 1 + super::MAX_REGISTERS],
+                len: 0,
+            }
+        }
+    }
+
+    impl Lp50xxInterface for Recorder {
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+            self.address = address;
+            self.len = data.len();
+            self.data[..data.len()].copy_from_slice(data);
+            Ok(())
+        }
+    }
+
+    /// Transport emulating the register file of a single chip
+    struct Chip {
+        registers: [u8; super::MAX_REGISTER_COUNT],
+        /// Register bits stuck low
+        stuck: u8,
+    }
+
+    impl Default for Chip {
+        fn default() -> Self {
+            Self {
+                registers: [0; super::MAX_REGISTER_COUNT],
+                stuck: 0,
+            }
+        }
+    }
+
+    impl Lp50xxInterface for Chip {
+        fn write(&mut self, _address: u8, data: &[u8]) -> Result<(), Error> {
+            let register = data[0] as usize;
+            for (stored, value) in self.registers[register..].iter_mut().zip(&data[1..]) {
+                *stored = value & !self.stuck;
+            }
+            Ok(())
+        }
+
+        fn write_read(
+            &mut self,
+            _address: u8,
+            data: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Error> {
+            let register = data[0] as usize;
+            buffer.copy_from_slice(&self.registers[register..register + buffer.len()]);
+            Ok(())
+        }
+    }
+
+    /// Bus with a number of consecutively addressed LP5012 chips
+    struct Bus {
+        chips: u8,
+    }
+
+    impl Lp50xxInterface for Bus {
+        fn write(&mut self, address: u8, _data: &[u8]) -> Result<(), Error> {
+            // Only the independent addresses beyond the chain are not acknowledged
+            if address < 0x14 + self.chips || address > 0x17 {
+                Ok(())
+            } else {
+                Err(Error::CommError(
+                    embedded_hal::i2c::ErrorKind::NoAcknowledge(
+                        embedded_hal::i2c::NoAcknowledgeSource::Address,
+                    ),
+                ))
+            }
+        }
+
+        fn write_read(
+            &mut self,
+            address: u8,
+            _data: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Error> {
+            if address.wrapping_sub(0x14) < self.chips {
+                Ok(())
+            } else {
+                Err(Error::CommError(
+                    embedded_hal::i2c::ErrorKind::NoAcknowledge(
+                        embedded_hal::i2c::NoAcknowledgeSource::Address,
+                    ),
+                ))
+            }
+        }
+    }
+
+    #[test]
+    fn config_changes_retain_other_bits() {
+        use super::registers::DEVICE_CONFIG1;
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin)
+                .enable(&mut Delay)
+                .unwrap();
+        controller.set_active_address(super::Address::Independent(0));
+
+        // Raw writes update the shadow of the configuration
+        controller.write_register(DEVICE_CONFIG1, 0x04).unwrap();
+        controller.set_global_off(true).unwrap();
+        assert_eq!(controller.read_device_config().unwrap(), 0x05);
+
+        // Settings written behind the driver's back are retained when read back first
+        let (mut chip, en) = controller.release();
+        chip.registers[DEVICE_CONFIG1 as usize] = 0x20;
+        let mut controller = super::LP50xx::init_with_interface(super::Model::LP5012, chip, en)
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_active_address(super::Address::Independent(0));
+        controller
+            .modify_config(|config| config.power_save(true))
+            .unwrap();
+        assert_eq!(controller.config().into_u8(), 0x30);
+        assert_eq!(controller.read_device_config().unwrap(), 0x30);
+    }
+
+    #[test]
+    fn resync_restores_registers() {
+        use super::registers::{lp5012, DEVICE_CONFIG0, DEVICE_CONFIG1};
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set_global_off(true).unwrap();
+        controller.set(2, 0x80).unwrap();
+
+        // Brown-out
+        controller.interface.registers = [0; super::MAX_REGISTER_COUNT];
+        controller.resync().unwrap();
+        let registers = &controller.interface.registers;
+        assert_eq!(registers[DEVICE_CONFIG0 as usize], 0x40);
+        assert_eq!(registers[DEVICE_CONFIG1 as usize], 0x3D);
+        assert_eq!(registers[lp5012::OUT0_COLOR as usize + 1], 0x80);
+    }
+
+    #[test]
+    fn power_loss_is_recovered_before_flush() {
+        use super::registers::{lp5012, DEVICE_CONFIG0};
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set_power_loss_handler(Some(|address| {
+            assert_eq!(address, super::Address::Independent(0))
+        }));
+        controller.set_buffered(true);
+        controller.set(1, 0x80).unwrap();
+        controller.flush().unwrap();
+        assert!(!controller.check_power_loss().unwrap());
+
+        controller.interface.registers = [0; super::MAX_REGISTER_COUNT];
+        controller.set(2, 0x40).unwrap();
+        controller.flush().unwrap();
+        let registers = &controller.interface.registers;
+        assert_eq!(registers[DEVICE_CONFIG0 as usize], 0x40);
+        assert_eq!(registers[lp5012::OUT0_COLOR as usize..][..2], [0x80, 0x40]);
+    }
+
+    #[test]
+    fn writes_are_verified() {
+        let chip = Chip {
+            stuck: 0x01,
+            ..Default::default()
+        };
+        let mut controller = super::LP50xx::init_with_interface(super::Model::LP5012, chip, Pin)
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_continuous_addressing(false);
+        controller.set_active_address(super::Address::Independent(0));
+        controller.set_verify_writes(true);
+
+        controller.set(1, 0x80).unwrap();
+        assert!(matches!(
+            controller.set(2, 0xFF),
+            Err(Error::VerifyFailed {
+                reg: 0x0C,
+                expected: 0xFF,
+                got: 0xFE
+            })
+        ));
+    }
+
+    /// Transport failing a number of writes with a NACK before succeeding
+    struct Flaky {
+        failures: u8,
+        attempts: u8,
+    }
+
+    impl Lp50xxInterface for Flaky {
+        fn write(&mut self, _address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                return Err(Error::CommError(
+                    embedded_hal::i2c::ErrorKind::NoAcknowledge(
+                        embedded_hal::i2c::NoAcknowledgeSource::Data,
+                    ),
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writes_are_retried() {
+        let flaky = Flaky {
+            failures: 2,
+            attempts: 0,
+        };
+        let mut controller = super::LP50xx::init_with_interface(super::Model::LP5012, flaky, Pin)
+            .into_monochromatic_mode()
+            .into_state::<super::Enabled>();
+        assert!(matches!(controller.set(1, 0xFF), Err(Error::CommError(_))));
+
+        controller.set_retry_policy(super::RetryPolicy::new(3).with_delay(10, |_| {}));
+        controller.set(1, 0xFF).unwrap();
+        assert_eq!(
+            controller.stats(),
+            super::BusStats {
+                failed_writes: 1,
+                retries: 1,
+                recovered: 1,
+                ..Default::default()
+            }
+        );
+        let (flaky, _) = controller.release();
+        assert_eq!(flaky.attempts, 3);
+    }
+
+    #[test]
+    fn chain_length_is_detected() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Bus { chips: 2 }, Pin)
+                .into_monochromatic_mode()
+                .enable(&mut Delay)
+                .unwrap();
+        assert_eq!(controller.scan().unwrap(), [true, true, false, false]);
+        assert_eq!(controller.detect_chain_length().unwrap(), 2);
+        controller.set(24, 0xFF).unwrap();
+        assert!(matches!(
+            controller.set(25, 0xFF),
+            Err(Error::LedOutOfRange)
+        ));
+
+        controller.interface.chips = 0;
+        assert!(matches!(
+            controller.detect_chain_length(),
+            Err(Error::InvalidAddress)
+        ));
+        assert_eq!(controller.num_leds(), 24);
+    }
+
+    #[test]
+    fn writes_through_interface() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5024, Recorder::default(), Pin)
+                .into_color_mode()
+                .enable(&mut Delay)
+                .unwrap();
+        controller.set(2, (0x80, [1, 2, 3])).unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(recorder.address, 0x3C);
+        assert_eq!(&recorder.data[..recorder.len], &[0x12, 1, 2, 3]);
+    }
+
+    #[test]
+    fn white_balance_scales_colors() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5024, Recorder::default(), Pin)
+                .into_color_mode()
+                .enable(&mut Delay)
+                .unwrap();
+        controller.set_white_balance([0xFF, 0x80, 0xFF]);
+        controller.set_channel_white_balance(Some(&[[0xFF; 3], [0xFF, 0xFF, 0x80]]));
+        controller.set(2, (0x80, [200, 200, 200])).unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(&recorder.data[..recorder.len], &[0x12, 200, 100, 100]);
+    }
+
+    #[test]
+    fn raw_registers_are_bounded_by_the_model() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin)
+                .enable(&mut Delay)
+                .unwrap();
+        let reset = super::registers::lp5012::RESET;
+        controller.write_register(reset, 0xFF).unwrap();
+        assert!(matches!(
+            controller.write_registers(reset, &[0, 0]),
+            Err(Error::InvalidRegister)
+        ));
+        let (recorder, _) = controller.release();
+        assert_eq!(&recorder.data[..recorder.len], &[reset, 0xFF]);
+    }
+
+    #[test]
+    fn write_frame_is_a_single_transaction() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Recorder::default(), Pin)
+                .enable(&mut Delay)
+                .unwrap();
+        controller
+            .write_frame_with_brightness(&[1, 2, 3], &[4, 5, 6])
+            .unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(&recorder.data[..recorder.len], &[0x07, 1, 2, 3, 0, 4, 5, 6]);
+    }
+
+    #[test]
+    fn fade_lands_on_the_target() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin)
+                .into_monochromatic_mode()
+                .enable(&mut Delay)
+                .unwrap();
+        controller.set_fade_steps(3);
+        controller.fade_to(2, 200, 30, &mut Delay).unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(recorder.address, 0x14);
+        assert_eq!(&recorder.data[..recorder.len], &[0x0C, 200]);
+    }
+
+    #[test]
+    fn bar_graph_lights_leds_proportionally() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Recorder::default(), Pin);
+        controller.set_chain_length(1).unwrap();
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_bar_graph(50, 0xFF, true).unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(
+            &recorder.data[..recorder.len],
+            &[
+                super::registers::lp5012::OUT0_COLOR,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0x7F,
+                0,
+                0,
+                0,
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn mode_changes_preserve_settings() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5030, Recorder::default(), Pin)
+                .into_color_mode();
+        controller.set_brightness_factor(0.5);
+        controller.set_chain_length(2).unwrap();
+        let controller = controller.into_monochromatic_mode();
+        assert_eq!(controller.brightness_factor(), 0.5);
+        assert_eq!(controller.chain_length(), 2);
+        assert!(matches!(controller.model(), super::Model::LP5030));
+    }
+
+    #[test]
+    fn base_address_can_be_overridden() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin)
+                .into_monochromatic_mode();
+        controller.set_base_address(Some(0x40));
+        controller.set_chain_length(2).unwrap();
+        let mut controller = controller.enable(&mut Delay).unwrap();
+        controller.set(13, 0xFF).unwrap();
+        let (recorder, _) = controller.release();
+        assert_eq!(recorder.address, 0x41);
+    }
+
     #[test]
     fn correct_led_address_offset() {
-        let offset = super::get_led_address_offset(1, super::Model::LP5012);
+        let offset = super::get_led_address_offset(1, 12);
         assert_eq!(offset, 0x00);
-        let offset = super::get_led_address_offset(12, super::Model::LP5012);
+        let offset = super::get_led_address_offset(12, 12);
         assert_eq!(offset, 0x00);
-        let offset = super::get_led_address_offset(13, super::Model::LP5012);
+        let offset = super::get_led_address_offset(13, 12);
         assert_eq!(offset, 0x01);
-        let offset = super::get_led_address_offset(24, super::Model::LP5012);
+        let offset = super::get_led_address_offset(24, 12);
         assert_eq!(offset, 0x01);
-        let offset = super::get_led_address_offset(25, super::Model::LP5012);
+        let offset = super::get_led_address_offset(25, 12);
         assert_eq!(offset, 0x02);
+        let offset = super::get_led_address_offset(37, 12);
+        assert_eq!(offset, 0x03);
+        let offset = super::get_led_address_offset(49, 12);
+        assert_eq!(offset, 0x04);
+    }
+
+    #[test]
+    fn correct_model_addressing() {
+        use super::{Address, Model};
+        assert_eq!(
+            Address::Broadcast.into_u8_for_model(Model::LP5012).unwrap(),
+            0x0C
+        );
+        assert_eq!(
+            Address::Independent(1)
+                .into_u8_for_model(Model::LP5012)
+                .unwrap(),
+            0x15
+        );
+        #[allow(deprecated)]
+        let legacy = Address::Independent(1).into_u8();
+        assert_eq!(legacy, 0x15);
+        assert_eq!(
+            Address::Broadcast.into_u8_for_model(Model::LP5024).unwrap(),
+            0x3C
+        );
+        assert_eq!(
+            Address::Independent(3)
+                .into_u8_for_model(Model::LP5018)
+                .unwrap(),
+            0x2B
+        );
+        assert_eq!(
+            super::get_channel_registers(1, Model::LP5012).unwrap(),
+            (0x07, 0x0B)
+        );
+        assert_eq!(
+            super::get_channel_registers(8, Model::LP5024).unwrap(),
+            (0x0E, 0x24)
+        );
+        assert_eq!(
+            Address::Independent(2)
+                .into_u8_for_model(Model::LP5036)
+                .unwrap(),
+            0x32
+        );
+        assert_eq!(
+            super::get_channel_registers(12, Model::LP5036).unwrap(),
+            (0x13, 0x35)
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        use super::{Address, Error, Model};
+        assert!(matches!(
+            Address::Independent(4).into_u8_for_model(Model::LP5012),
+            Err(Error::InvalidAddress)
+        ));
+        assert!(matches!(Address::try_new(4), Err(Error::InvalidAddress)));
+        assert_eq!(Address::try_new(3).unwrap(), Address::Independent(3));
+        assert!(matches!(
+            super::get_channel_registers(0, Model::LP5012),
+            Err(Error::InvalidChannel)
+        ));
+        assert!(matches!(
+            super::get_channel_registers(4, Model::LP5009),
+            Err(Error::InvalidChannel)
+        ));
+    }
+
+    #[test]
+    fn correct_led_address_offset_lp5036() {
+        let offset = super::get_led_address_offset(36, 36);
+        assert_eq!(offset, 0x00);
+        let offset = super::get_led_address_offset(37, 36);
+        assert_eq!(offset, 0x01);
+    }
+
+    #[test]
+    fn leds_beyond_the_chain_are_out_of_range() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        controller.set_chain_length(2).unwrap();
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        assert!(controller.set(24, 0xFF).is_ok());
+        assert!(matches!(
+            controller.set(25, 0xFF),
+            Err(Error::LedOutOfRange)
+        ));
+    }
+
+    #[cfg(feature = "disable-on-drop")]
+    #[test]
+    fn dropping_disables_outputs() {
+        use core::cell::Cell;
+
+        /// Enable line recording its level
+        struct Line<'a>(&'a Cell<bool>);
+
+        impl embedded_hal::digital::ErrorType for Line<'_> {
+            type Error = core::convert::Infallible;
+        }
+
+        impl embedded_hal::digital::OutputPin for Line<'_> {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0.set(false);
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.0.set(true);
+                Ok(())
+            }
+        }
+
+        let level = Cell::new(false);
+        let controller = super::LP50xx::init_with_interface(
+            super::Model::LP5012,
+            Recorder::default(),
+            Line(&level),
+        );
+        let controller = controller.enable(&mut Delay).unwrap();
+        assert!(level.get());
+        let (_, line) = controller.into_color_mode().release();
+        assert!(level.get());
+
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), line);
+        drop(controller.enable(&mut Delay).unwrap());
+        assert!(!level.get());
+    }
+
+    #[test]
+    fn modes_convert_freely() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        controller.set(1, (0xFF, [0x10, 0x20, 0x30])).unwrap();
+
+        let mut controller = controller.into_monochromatic_mode();
+        controller.set(4, 0x40).unwrap();
+        let mut controller = controller.into_default_mode().into_color_mode();
+        controller.set(2, (0xFF, [0x50, 0x60, 0x70])).unwrap();
+
+        let scene = controller.snapshot();
+        let controller = controller.into_default_mode();
+        assert_eq!(controller.snapshot(), scene);
+        let (recorder, _) = controller.release();
+        assert_eq!(recorder.data[..4], [0x0E, 0x50, 0x60, 0x70]);
+    }
+
+    #[test]
+    fn color_and_brightness_are_set_separately() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        controller.set_color(2, [0x10, 0x20, 0x30]).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0E, 0x10, 0x20, 0x30]
+        );
+
+        controller.set_brightness_factor(0.5);
+        controller.set_brightness(2, 0xFF).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x08, 0x7F]
+        );
+    }
+
+    #[test]
+    fn colors_are_written_per_chip() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        controller.set_chain_length(2).unwrap();
+        let values = [(0xFF, [1, 2, 3]); 5];
+        controller.write_colors(&values).unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0xFF, 0, 0, 0, 1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert!(matches!(
+            controller.write_colors(&[(0, [0; 3]); 9]),
+            Err(Error::InvalidChannel)
+        ));
+    }
+
+    #[test]
+    fn all_leds_are_set_per_chip() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(2).unwrap();
+        let values: [u8; 14] = core::array::from_fn(|led| led as u8);
+        controller.set_all(&values).unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0B, 12, 13]
+        );
+        assert!(matches!(
+            controller.set_all(&[0; 25]),
+            Err(Error::LedOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn leds_are_assigned_to_the_bank_by_channel() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(2).unwrap();
+        controller.assign_leds_to_bank(&[5, 6, 14]).unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x02, 0x01]
+        );
+
+        controller.set_continuous_addressing(false);
+        controller.assign_leds_to_bank(&[12]).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x02, 0x08]
+        );
+        controller.set_bank_brightness(0x80).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x03, 0x80]
+        );
+    }
+
+    #[test]
+    fn model_geometry_is_public() {
+        let model = super::Model::LP5036;
+        assert_eq!(model.get_pin_count(), 36);
+        assert_eq!(model.rgb_channel_count(), 12);
+        assert_eq!(model.brightness_base(), 0x08);
+        assert_eq!(model.out_color_base(), 0x14);
+    }
+
+    #[test]
+    fn capabilities_follow_the_chain() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5024, Recorder::default(), Pin);
+        assert_eq!(controller.num_channels(), 8);
+        assert_eq!(controller.num_leds(), 24);
+        controller.set_chain_length(2).unwrap();
+        assert_eq!(controller.num_leds(), 48);
+        controller.set_continuous_addressing(false);
+        assert_eq!(controller.num_leds(), 24);
+        assert_eq!(controller.max_chain_len(), 4);
+    }
+
+    #[test]
+    fn many_leds_are_batched() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(2).unwrap();
+        controller
+            .set_many([(15, 3), (1, 1), (14, 2), (2, 1)].iter().copied())
+            .unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0C, 2, 3]
+        );
+
+        let mut controller = controller.into_color_mode();
+        controller
+            .set_many(
+                [(2, (0xFF, [1, 2, 3])), (1, (0xFF, [4, 5, 6]))]
+                    .iter()
+                    .copied(),
+            )
+            .unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0B, 4, 5, 6, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn brightness_addressing_drives_brightness_registers() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(4).unwrap();
+        controller.set_brightness_addressing(true).unwrap();
+        assert_eq!(controller.interface.len, 13);
+        assert_eq!(controller.interface.data[0], 0x0B);
+        assert_eq!(controller.interface.data[1..13], [0xFF; 12]);
+        assert_eq!(controller.num_leds(), 16);
+
+        controller.set(5, 0x80).unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0x80]
+        );
+        controller.fill(0x40).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0x40, 0x40, 0x40, 0x40]
+        );
+        assert!(matches!(
+            controller.set(17, 0x80),
+            Err(Error::LedOutOfRange)
+        ));
+        assert!(matches!(controller.led(17), Err(Error::LedOutOfRange)));
+    }
+
+    #[test]
+    fn masked_leds_are_skipped() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set_led_mask(&[2, 12]).unwrap();
+        assert!(controller.is_led_masked(12));
+        assert!(matches!(controller.set(2, 0xFF), Err(Error::LedMasked)));
+
+        controller.set_range(1..=3, 0x80).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0B, 0x80, 0x00, 0x80]
+        );
+        controller.fill(0x40).unwrap();
+        assert_eq!(controller.interface.address, 0x14);
+        assert_eq!(controller.interface.data[..4], [0x0B, 0x40, 0x00, 0x40]);
+        assert_eq!(controller.interface.data[11..13], [0x40, 0x00]);
+    }
+
+    #[test]
+    fn thermal_limit_derates_writes() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_thermal_limit(150);
+        assert_eq!(controller.thermal_limit(), 100);
+
+        controller.set_thermal_limit(50);
+        controller.set_brightness_factor(0.5);
+        controller.set(1, 0xFF).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0B, 63]
+        );
+    }
+
+    #[test]
+    fn ambient_scale_dims_writes() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        controller.set_ambient_scale(0x80);
+        controller.set_brightness(1, 0xFF).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0x80]
+        );
+    }
+
+    #[test]
+    fn flushes_are_throttled() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        static NOW: AtomicU32 = AtomicU32::new(1000);
+
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_buffered(true);
+        controller.set_frame_limit(20, || NOW.load(Ordering::Relaxed));
+
+        controller.set(1, 0x10).unwrap();
+        controller.flush().unwrap();
+        assert_eq!(controller.interface.data[..2], [0x0B, 0x10]);
+
+        NOW.store(1010, Ordering::Relaxed);
+        controller.set(2, 0x20).unwrap();
+        controller.flush().unwrap();
+        assert_eq!(controller.interface.data[..2], [0x0B, 0x10]);
+
+        NOW.store(1020, Ordering::Relaxed);
+        controller.flush().unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0C, 0x20]
+        );
+    }
+
+    #[test]
+    fn write_observer_sees_every_transaction() {
+        use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        static REGISTER: AtomicU8 = AtomicU8::new(0);
+
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_write_observer(|address, data| {
+            assert_eq!(address, super::Address::Independent(0));
+            WRITES.fetch_add(1, Ordering::Relaxed);
+            REGISTER.store(data[0], Ordering::Relaxed);
+        });
+        controller.set(2, 0x10).unwrap();
+        assert_eq!(WRITES.load(Ordering::Relaxed), 1);
+        assert_eq!(REGISTER.load(Ordering::Relaxed), 0x0C);
+
+        controller.clear_write_observer();
+        controller.set(3, 0x10).unwrap();
+        assert_eq!(WRITES.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn long_writes_are_chunked() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_max_write_len(5);
+        controller
+            .set_all(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+            .unwrap();
+        assert_eq!(
+            controller.interface.registers[0x0B..0x15],
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        );
+
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_max_write_len(5);
+        controller
+            .set_all(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+            .unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x13, 9, 10]
+        );
+    }
+
+    #[test]
+    fn bus_recovery_resyncs_the_chips() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set(1, 0x10).unwrap();
+        controller.interface.registers = [0; super::MAX_REGISTER_COUNT];
+
+        controller.recover_bus(&mut Pin, &mut Delay).unwrap();
+        assert_eq!(
+            controller.interface.registers[0x00],
+            super::registers::CHIP_EN
+        );
+        assert_eq!(controller.interface.registers[0x0B], 0x10);
+    }
+
+    #[test]
+    fn nacks_are_skipped_when_tolerated() {
+        let mut controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Bus { chips: 2 }, Pin)
+                .into_monochromatic_mode()
+                .enable(&mut Delay)
+                .unwrap();
+        controller.set_chain_length(3).unwrap();
+        assert!(matches!(controller.fill(0x10), Ok(())));
+        assert!(matches!(controller.set(25, 0xFF), Err(Error::CommError(_))));
+
+        controller.set_nack_tolerant(true);
+        controller.set_all(&[0xFF; 36]).unwrap();
+        let stats = controller.stats();
+        assert_eq!((stats.skipped, stats.nacked_chips), (1, 0b100));
+    }
+
+    #[test]
+    fn enable_follows_timing() {
+        /// Delay provider accumulating the time waited
+        struct Elapsed(u64);
+
+        impl embedded_hal::delay::DelayNs for Elapsed {
+            fn delay_ns(&mut self, ns: u32) {
+                self.0 += ns as u64;
+            }
+        }
+
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut elapsed = Elapsed(0);
+        let controller = controller.enable(&mut elapsed).unwrap();
+        assert_eq!(elapsed.0, 11_000_000);
+
+        let mut controller = controller.reset(&mut elapsed).unwrap();
+        controller.set_timing(super::Timing {
+            enable_low_us: 100,
+            startup_us: 500,
+            ..Default::default()
+        });
+        elapsed.0 = 0;
+        controller.enable(&mut elapsed).unwrap();
+        assert_eq!(elapsed.0, 600_000);
+    }
+
+    #[test]
+    fn timed_enable_owns_delay() {
+        /// Delay provider accumulating the time waited
+        struct Elapsed(u64);
+
+        impl embedded_hal::delay::DelayNs for Elapsed {
+            fn delay_ns(&mut self, ns: u32) {
+                self.0 += ns as u64;
+            }
+        }
+
+        let mut controller = super::LP50xx::init_with_interface(
+            super::Model::LP5012,
+            Recorder::default(),
+            super::TimedEnable::new(Pin, Elapsed(0)),
+        );
+        controller.set_timing(super::Timing {
+            enable_low_us: 100,
+            startup_us: 500,
+            ..Default::default()
+        });
+        let controller = controller.enable_timed().unwrap();
+        assert_eq!(controller.timing().startup_us, 500);
+        let controller = controller.reset_timed().unwrap();
+
+        let (recorder, enable) = controller.release();
+        let reset = super::Model::LP5012.get_reset_register();
+        assert_eq!(recorder.data[..2], [reset, 0xff]);
+        let (_, elapsed) = enable.into_inner();
+        assert_eq!(elapsed.0, 12_100_000);
+    }
+
+    #[test]
+    fn standby_is_not_power_loss() {
+        use super::registers::{CHIP_EN, DEVICE_CONFIG0};
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set_power_loss_handler(Some(|_| panic!("standby reported as power loss")));
+
+        controller.standby().unwrap();
+        assert!(controller.is_standby(super::Address::Broadcast));
+        assert_eq!(controller.interface.registers[DEVICE_CONFIG0 as usize], 0);
+        assert!(!controller.check_power_loss().unwrap());
+        controller.resync().unwrap();
+        assert_eq!(controller.interface.registers[DEVICE_CONFIG0 as usize], 0);
+
+        controller.wakeup().unwrap();
+        assert!(!controller.is_standby(super::Address::Independent(0)));
+        assert_eq!(
+            controller.interface.registers[DEVICE_CONFIG0 as usize],
+            CHIP_EN
+        );
+    }
+
+    #[test]
+    fn colors_skip_reserved_brightness_registers() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        let values = [(0x10, [1, 2, 3]), (0x20, [4, 5, 6]), (0x30, [7, 8, 9])];
+        controller.write_colors(&values).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0x10, 0x20, 0x30, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+    }
+
+    #[test]
+    fn fill_color_skips_reserved_brightness_registers() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        controller.fill_color(0x80, [1, 2, 3]).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x07, 0x80, 0x80, 0x80, 0, 1, 2, 3, 1, 2, 3, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn resync_restores_the_last_output() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_chain_length(1).unwrap();
+        controller.set(9, 0x80).unwrap();
+
+        // Brown-out
+        controller.interface.registers = [0; super::MAX_REGISTER_COUNT];
+        controller.resync().unwrap();
+        let out8 = super::registers::lp5012::OUT0_COLOR as usize + 8;
+        assert_eq!(controller.interface.registers[out8], 0x80);
+    }
+
+    #[test]
+    fn raw_writes_shadow_the_last_output() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5009, Recorder::default(), Pin);
+        let mut controller = controller.enable(&mut Delay).unwrap();
+        let out8 = super::registers::lp5012::OUT0_COLOR + 8;
+        controller.write_register(out8, 0x55).unwrap();
+        let frame = controller.frame.get(super::Address::Independent(0), 12, 1);
+        assert_eq!(frame.unwrap(), &[0x55]);
+    }
+
+    #[test]
+    fn validated_indices_are_accepted() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.into_color_mode().enable(&mut Delay).unwrap();
+        let channel = controller.channel(2).unwrap();
+        controller.set_brightness(channel, 0x80).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x08, 0x80]
+        );
+        controller.set_hsv(channel, 0, 0, 0xFF).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0E, 0xFF, 0xFF, 0xFF]
+        );
+
+        let mut controller = controller.into_monochromatic_mode();
+        controller.set_chain_length(2).unwrap();
+        let led = super::Led::new(13, super::Model::LP5012, 2).unwrap();
+        controller.set(led, 0x40).unwrap();
+        assert_eq!(controller.interface.address, 0x15);
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [0x0B, 0x40]
+        );
+    }
+
+    #[test]
+    fn clear_keeps_brightness_addressing() {
+        use super::registers::lp5012;
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Chip::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_continuous_addressing(false);
+        controller.set_active_address(super::Address::Independent(0));
+        controller.set_brightness_addressing(true).unwrap();
+        controller.clear().unwrap();
+        controller.set(1, 0xFF).unwrap();
+
+        let registers = &controller.interface.registers;
+        assert_eq!(registers[lp5012::LED0_BRIGHTNESS as usize], 0xFF);
+        assert_eq!(registers[lp5012::OUT0_COLOR as usize..][..3], [0xFF; 3]);
+    }
+
+    #[test]
+    fn reset_forgets_registers() {
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller
+            .into_monochromatic_mode()
+            .enable(&mut Delay)
+            .unwrap();
+        controller.set_log_scale(false).unwrap();
+        controller.set_brightness_addressing(true).unwrap();
+        controller.standby().unwrap();
+        controller.set(1, 0x80).unwrap();
+
+        let mut controller = controller
+            .reset(&mut Delay)
+            .unwrap()
+            .enable(&mut Delay)
+            .unwrap();
+        assert!(!controller.brightness_addressing());
+        assert!(!controller.is_standby(super::Address::Broadcast));
+        let frame = controller.frame.get(super::Address::Independent(0), 0, 16);
+        assert_eq!(frame.unwrap(), &[0; 16]);
+        controller.set_power_save(true).unwrap();
+        assert_eq!(
+            controller.interface.data[..controller.interface.len],
+            [
+                super::registers::DEVICE_CONFIG1,
+                super::DeviceConfig::default().into_u8()
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_writes_shadow_the_device_config() {
+        use super::registers::{CHIP_EN, DEVICE_CONFIG0};
+        let controller =
+            super::LP50xx::init_with_interface(super::Model::LP5012, Recorder::default(), Pin);
+        let mut controller = controller.enable(&mut Delay).unwrap();
+        controller
+            .write_registers(DEVICE_CONFIG0, &[0, 0x3C])
+            .unwrap();
+        assert!(controller.is_standby(super::Address::Broadcast));
+        assert_eq!(controller.config.into_u8(), 0x3C);
+
+        controller.write_register(DEVICE_CONFIG0, CHIP_EN).unwrap();
+        assert!(!controller.is_standby(super::Address::Independent(0)));
     }
 }
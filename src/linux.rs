@@ -0,0 +1,28 @@
+//! Linux support through `linux-embedded-hal`, enabled with the `linux` feature, for exercising an
+//! LP50xx from a single board computer or a USB to I2C adapter
+//!
+//! ```ignore
+//! let controller = lp50xx::linux::open(Model::LP5012, "/dev/i2c-1")?;
+//! let mut controller = controller.into_color_mode().enable(&mut Delay)?;
+//! controller.set(1, (0xFF, [0xFF, 0, 0]))?;
+//! ```
+
+use crate::{DefaultMode, Disabled, I2cInterface, LP50xx, Model, NoEnable};
+use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
+use linux_embedded_hal::I2cdev;
+use std::path::Path;
+
+/// The LP50xx on a Linux I2C bus device, with the EN line tied high
+pub type LinuxLP50xx<MODE, STATE> = LP50xx<MODE, I2cInterface<I2cdev>, NoEnable, STATE>;
+
+/// Open an I2C bus device and initialize the LP50xx on it. The EN line is expected to be tied high,
+/// use `LP50xx::init_with_i2c` with a `linux_embedded_hal::CdevPin` when it is wired to a GPIO
+/// * `model` - The model of the LP50xx
+/// * `path` - Path of the bus device, for example `/dev/i2c-1`
+pub fn open<P: AsRef<Path>>(
+    model: Model,
+    path: P,
+) -> Result<LinuxLP50xx<DefaultMode, Disabled>, LinuxI2CError> {
+    let i2c = I2cdev::new(path)?;
+    Ok(LP50xx::init_with_i2c(model, i2c, NoEnable))
+}
@@ -0,0 +1,443 @@
+//! Generation of the blocking and asynchronous driver methods from a single definition, so the two
+//! drivers cannot drift apart as features are added. Each template takes the variant to generate,
+//! `blocking` or `asynch`, and is expanded in the impl blocks of `lib.rs` and `asynch.rs`
+
+/// Perform bus or delay IO, awaiting it in the asynchronous variant
+macro_rules! io {
+    (blocking, $($call:tt)*) => {
+        $($call)*
+    };
+    (asynch, $s:ident . write ( $($args:tt)* )) => {
+        $s.write_async($($args)*).await
+    };
+    (asynch, $s:ident . write_transaction ( $($args:tt)* )) => {
+        $s.write_transaction_async($($args)*).await
+    };
+    (asynch, $s:ident . write_once ( $($args:tt)* )) => {
+        $s.write_once_async($($args)*).await
+    };
+    (asynch, $s:ident . verify ( $($args:tt)* )) => {
+        $s.verify_async($($args)*).await
+    };
+    (asynch, $s:ident . write_outputs ( $($args:tt)* )) => {
+        $s.write_outputs_async($($args)*).await
+    };
+    (asynch, $s:ident . configure_with ( $($args:tt)* )) => {
+        $s.configure_with_async($($args)*).await
+    };
+    (asynch, $s:ident . tick ( $($args:tt)* )) => {
+        $s.tick_async($($args)*).await
+    };
+    (asynch, $($call:tt)*) => {
+        $($call)*.await
+    };
+}
+
+/// Wait between write attempts. The asynchronous variant awaits the transport rather than calling the
+/// blocking delay function, which would stall the executor
+macro_rules! retry_delay {
+    (blocking, $s:ident, $delay_us:expr, $delay:expr) => {
+        $delay($delay_us)
+    };
+    (asynch, $s:ident, $delay_us:expr, $delay:expr) => {{
+        let _ = $delay;
+        $s.interface.retry_delay($delay_us).await
+    }};
+}
+
+/// Detect and recover chips reset by a power loss, which requires reads and is only supported by the
+/// blocking variant
+macro_rules! check_power_loss {
+    (blocking, $s:ident) => {
+        if $s.power_loss.is_some() {
+            $s.check_power_loss()?;
+        }
+    };
+    (asynch, $s:ident) => {};
+}
+
+/// Define a method named `blocking_name / async_name` as a blocking or asynchronous method
+macro_rules! maybe_async_fn {
+    (blocking, $(#[$attr:meta])* $vis:vis fn $name:ident / $async_name:ident $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis fn $name $($rest)*
+    };
+    (asynch, $(#[$attr:meta])* $vis:vis fn $name:ident / $async_name:ident $($rest:tt)*) => {
+        #[doc = concat!("The asynchronous counterpart of `", stringify!($name), "`, not blocking the executor")]
+        #[doc = ""]
+        $(#[$attr])*
+        $vis async fn $async_name $($rest)*
+    };
+}
+
+/// Methods writing through the transport, in any mode and state
+macro_rules! transport_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Write a single transaction to the LP50xx using the configured transport, retrying it by the retry policy
+            /// * `addr` - Address of the LP50xx
+            /// * `data` - The data payload to be sent
+            fn write_transaction / write_transaction_async(&mut self, addr: Address, data: &[u8]) -> Result<(), Error> {
+                let mut attempt = 1;
+                loop {
+                    match io!($variant, self.write_once(addr, data)) {
+                        Err(Error::CommError(_) | Error::VerifyFailed { .. })
+                            if attempt < self.retry.attempts =>
+                        {
+                            attempt += 1;
+                            self.stats.retries = self.stats.retries.saturating_add(1);
+                            if let Some((delay_us, delay)) = self.retry.delay {
+                                retry_delay!($variant, self, delay_us, delay);
+                            }
+                        }
+                        Err(error) => {
+                            self.stats.failed_writes = self.stats.failed_writes.saturating_add(1);
+                            return Err(error);
+                        }
+                        Ok(()) => {
+                            if attempt > 1 {
+                                self.stats.recovered = self.stats.recovered.saturating_add(1);
+                            }
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Write data to the LP50xx in a single attempt, verifying it when enabled
+            /// * `addr` - Address of the LP50xx
+            /// * `data` - The data payload to be sent
+            fn write_once / write_once_async(&mut self, addr: Address, data: &[u8]) -> Result<(), Error> {
+                let address = self.get_i2c_address(addr)?;
+                self.observe_write(addr, data);
+                io!($variant, self.interface.write(address, data))?;
+                if self.verify_writes {
+                    io!($variant, self.verify(addr, data))?;
+                }
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Read back the registers of a write and compare them with the written values
+            /// * `addr` - Address of the LP50xx
+            /// * `data` - The data payload which was sent
+            fn verify / verify_async(&mut self, addr: Address, data: &[u8]) -> Result<(), Error> {
+                let (register, values) = match (addr, data.split_first()) {
+                    (Address::Independent(_), Some((register, values))) => (*register, values),
+                    _ => return Ok(()),
+                };
+                // The reset register does not hold the written value
+                let count = values
+                    .len()
+                    .min(self.model.get_reset_register().saturating_sub(register) as usize);
+                if count == 0 {
+                    return Ok(());
+                }
+
+                let address = self.get_i2c_address(addr)?;
+                let mut buffer = [0u8; MAX_REGISTER_COUNT];
+                io!($variant, self.interface.write_read(address, &[register], &mut buffer[..count]))?;
+                match (register..)
+                    .zip(&values[..count])
+                    .zip(&buffer[..count])
+                    .find(|((_, expected), got)| expected != got)
+                {
+                    Some(((reg, expected), got)) => Err(Error::VerifyFailed {
+                        reg,
+                        expected: *expected,
+                        got: *got,
+                    }),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Write data to the LP50xx, splitting auto-increment writes longer than the maximum transaction
+            /// length into transactions of consecutive registers, and skipping writes which are not
+            /// acknowledged with NACK tolerance enabled
+            /// * `addr` - Address of the LP50xx
+            /// * `data` - The register address followed by the register values
+            fn write / write_async(&mut self, addr: Address, data: &[u8]) -> Result<(), Error> {
+                if data.len() <= self.max_write_len {
+                    let result = io!($variant, self.write_transaction(addr, data));
+                    return self.tolerate_nack(addr, result);
+                }
+                let chunk = self.max_write_len - 1;
+                let mut payload = [0u8; 1 + MAX_REGISTER_COUNT];
+                for (index, values) in data[1..].chunks(chunk).enumerate() {
+                    payload[0] = data[0] + (index * chunk) as u8;
+                    payload[1..=values.len()].copy_from_slice(values);
+                    let result = io!($variant, self.write_transaction(addr, &payload[..=values.len()]));
+                    self.tolerate_nack(addr, result)?;
+                }
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Write consecutive brightness or color registers, deferring the write in buffered mode
+            /// * `addr` - Address of the LP50xx
+            /// * `register` - Address of the first register
+            /// * `values` - The register values
+            fn write_outputs / write_outputs_async(
+                &mut self,
+                addr: Address,
+                register: u8,
+                values: &[u8],
+            ) -> Result<(), Error> {
+                if !self.buffered {
+                    let mut payload = [0u8; 1 + MAX_REGISTERS];
+                    payload[0] = register;
+                    payload[1..=values.len()].copy_from_slice(values);
+                    io!($variant, self.write(addr, &payload[..=values.len()]))?;
+                }
+                self.update_frame(addr, register, values)
+            }
+        }
+    };
+}
+
+/// Methods of the disabled LP50xx
+macro_rules! disabled_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Enable the LP50xx, this must be executed prior to any commands sent to the LP50xx
+            /// * `delay` - delay provider
+            pub fn enable / enable_async<DELAY>(
+                mut self,
+                delay: &mut DELAY,
+            ) -> Result<LP50xx<MODE, IFACE, EN, Enabled>, Error>
+            where
+                DELAY: DelayNs,
+            {
+                self.enable.set_low().map_err(|_| Error::EnableLine)?;
+                io!($variant, delay.delay_us(self.timing.enable_low_us));
+                self.enable.set_high().map_err(|_| Error::EnableLine)?;
+                io!($variant, delay.delay_us(self.timing.startup_us));
+                io!($variant, self.write(
+                    Address::Broadcast,
+                    &[registers::DEVICE_CONFIG0, registers::CHIP_EN],
+                ))?;
+                self.standby_chips = 0;
+                Ok(self.into_state())
+            }
+        }
+    };
+}
+
+/// Methods of the enabled LP50xx, in any mode
+macro_rules! enabled_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Reset the LP50xx, after which it must be enabled again. The shadowed configuration and output
+            /// registers return to their defaults, as do brightness addressing and standby
+            /// Currently resetting is only available for Broadcast, see `reset_chip` for resetting a single chip
+            /// * `delay` - delay provider
+            pub fn reset / reset_async<DELAY>(
+                mut self,
+                delay: &mut DELAY,
+            ) -> Result<LP50xx<MODE, IFACE, EN, Disabled>, Error>
+            where
+                DELAY: DelayNs,
+            {
+                let reset = self.model.get_reset_register();
+                io!($variant, self.write(Address::Broadcast, &[reset, 0xff]))?;
+                self.forget_registers();
+                io!($variant, delay.delay_us(self.timing.reset_us));
+                self.enable.set_low().map_err(|_| Error::EnableLine)?;
+                io!($variant, delay.delay_us(self.timing.shutdown_us));
+                self.enable.set_high().map_err(|_| Error::EnableLine)?;
+                io!($variant, delay.delay_us(self.timing.startup_us));
+                Ok(self.into_state())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Configure the LP50xx. For information regarding each of these settings, please consult the datasheet.
+            /// The active address is configured, which is Broadcast by default, so chips in a chain can receive different settings
+            /// * `log_scale` - Logarithmic scale dimming curve
+            /// * `power_save` - Automatic power-saving mode enabled
+            /// * `auto_incr` - The auto-increment feature allows writing or reading several consecutive registers within one transmission.
+            /// * `pwm_dithering` - PWM dithering mode enabled
+            /// * `max_current_option` - Output maximum current enable: IMAX = 35 mA, disable: IMAX = 25.5mA
+            /// * `global_off` - Shut down all LEDs when enabled
+            pub fn configure / configure_async(
+                &mut self,
+                log_scale: bool,
+                power_save: bool,
+                auto_incr: bool,
+                pwm_dithering: bool,
+                max_current_option: bool,
+                global_off: bool,
+            ) -> Result<(), Error> {
+                let config = DeviceConfig::default()
+                    .log_scale(log_scale)
+                    .power_save(power_save)
+                    .auto_incr(auto_incr)
+                    .pwm_dithering(pwm_dithering)
+                    .max_current_option(max_current_option)
+                    .global_off(global_off);
+
+                io!($variant, self.configure_with(config))
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Configure the LP50xx using a typed `DeviceConfig`
+            /// The active address is configured, which is Broadcast by default
+            /// * `config` - The configuration encoded into DEVICE_CONFIG1
+            pub fn configure_with / configure_with_async(&mut self, config: DeviceConfig) -> Result<(), Error> {
+                io!($variant, self.write(
+                    self.active_address,
+                    &[registers::DEVICE_CONFIG1, config.into_u8()],
+                ))?;
+                self.config = config;
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Put the LP50xx in standby by clearing the CHIP_EN bit, a low power state retaining the registers
+            /// without toggling the EN line, for designs where it is tied high.
+            /// The active address is put in standby, which is Broadcast by default
+            pub fn standby / standby_async(&mut self) -> Result<(), Error> {
+                io!($variant, self.write(self.active_address, &[registers::DEVICE_CONFIG0, 0]))?;
+                self.standby_chips |= self.chip_mask(self.active_address);
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Wake the LP50xx from standby by setting the CHIP_EN bit
+            /// The active address is woken, which is Broadcast by default
+            pub fn wakeup / wakeup_async(&mut self) -> Result<(), Error> {
+                io!($variant, self.write(
+                    self.active_address,
+                    &[registers::DEVICE_CONFIG0, registers::CHIP_EN],
+                ))?;
+                self.standby_chips &= !self.chip_mask(self.active_address);
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Write all brightness and color registers changed since the last flush, each run of consecutive
+            /// registers is sent in a single auto-increment transaction. Nothing is written within the
+            /// minimum interval of the previous frame, see `set_frame_limit`
+            pub fn flush / flush_async(&mut self) -> Result<(), Error> {
+                if self.throttle_frame() {
+                    return Ok(());
+                }
+                check_power_loss!($variant, self);
+                let base = self.model.get_brightness_base();
+                let mut payload = [0u8; 1 + MAX_REGISTERS];
+                while let Some((addr, offset, values)) = self.frame.dirty_run() {
+                    let len = values.len();
+                    payload[0] = base + offset as u8;
+                    payload[1..=len].copy_from_slice(values);
+                    io!($variant, self.write(addr, &payload[..=len]))?;
+                    self.frame.clean(addr, offset, len);
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Methods of the enabled LP50xx in color mode
+macro_rules! color_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Set the channel brightness and RGB values, the brightness is scaled by the brightness factor
+            /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+            /// * `value` - the channel brightness and RGB values
+            pub fn set / set_async(
+                &mut self,
+                channel: impl ChannelIndex,
+                (brightness, [r, g, b]): (u8, [u8; 3]),
+            ) -> Result<(), Error> {
+                let channel = channel.channel_index();
+                let (bright_addr, color_addr) = get_channel_registers(channel, self.model)?;
+                let brightness = self.scale(brightness);
+                let color = self.correct_color(channel, [r, g, b]);
+                io!($variant, self.write_outputs(self.active_address, bright_addr, &[brightness]))?;
+                io!($variant, self.write_outputs(self.active_address, color_addr, &color))?;
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Set the brightness and RGB values of consecutive channels beginning at channel 1, writing each chip in
+            /// a single auto-increment transaction. With continuous addressing the channels continue across the chips
+            /// in the chain, otherwise the channels of the active address are set
+            /// * `values` - the brightness and RGB values of each channel
+            pub fn write_colors / write_colors_async(&mut self, values: &[(u8, [u8; 3])]) -> Result<(), Error> {
+                let channels = self.model.get_channel_count() as usize;
+                let chips = if self.continuous_addressing {
+                    self.chain_length as usize
+                } else {
+                    1
+                };
+                if values.len() > channels * chips {
+                    return Err(Error::InvalidChannel);
+                }
+
+                let base = self.model.get_brightness_base();
+                let len = self.model.get_output_count();
+                for (chip, values) in (0..).zip(values.chunks(channels)) {
+                    let (address, registers) = self.encode_colors(chip, values)?;
+                    io!($variant, self.write_outputs(address, base, &registers[..len]))?;
+                }
+                Ok(())
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Set the RGB values of a channel, leaving its brightness unchanged
+            /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+            /// * `color` - the channel RGB values
+            pub fn set_color / set_color_async(&mut self, channel: impl ChannelIndex, color: [u8; 3]) -> Result<(), Error> {
+                let channel = channel.channel_index();
+                let (_, color_addr) = get_channel_registers(channel, self.model)?;
+                let color = self.correct_color(channel, color);
+                io!($variant, self.write_outputs(self.active_address, color_addr, &color))
+            }
+        }
+
+        maybe_async_fn! { $variant,
+            /// Set the brightness of a channel, leaving its RGB values unchanged. The brightness is scaled by the
+            /// brightness factor
+            /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+            /// * `brightness` - the channel brightness
+            pub fn set_brightness / set_brightness_async(
+                &mut self,
+                channel: impl ChannelIndex,
+                brightness: u8,
+            ) -> Result<(), Error> {
+                let channel = channel.channel_index();
+                let (bright_addr, _) = get_channel_registers(channel, self.model)?;
+                let brightness = self.scale(brightness);
+                io!($variant, self.write_outputs(self.active_address, bright_addr, &[brightness]))
+            }
+        }
+    };
+}
+
+/// Methods of the enabled LP50xx in monochromatic mode
+macro_rules! monochromatic_methods {
+    ($variant:ident) => {
+        maybe_async_fn! { $variant,
+            /// Set the desired LED value
+            /// * `led` - the LED index beginning at 1
+            /// * `value` - luminosity value
+            pub fn set / set_async(&mut self, led: impl LedIndex, value: u8) -> Result<(), Error> {
+                let (address, register, value) = self.get_led_write(led.led_index(), value)?;
+                io!($variant, self.write_outputs(address, register, &[value]))
+            }
+        }
+    };
+}
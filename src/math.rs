@@ -0,0 +1,28 @@
+//! Floating point functions of the selected math backend, `libm` takes precedence over `micromath`
+
+#[cfg(not(feature = "libm"))]
+use micromath::F32Ext;
+
+/// Raise `x` to the power of `y`
+#[cfg(feature = "libm")]
+pub(crate) fn powf(x: f32, y: f32) -> f32 {
+    libm::powf(x, y)
+}
+
+/// Raise `x` to the power of `y`
+#[cfg(not(feature = "libm"))]
+pub(crate) fn powf(x: f32, y: f32) -> f32 {
+    F32Ext::powf(x, y)
+}
+
+/// Round `x` to the nearest integer, away from zero
+#[cfg(feature = "libm")]
+pub(crate) fn roundf(x: f32) -> f32 {
+    libm::roundf(x)
+}
+
+/// Round `x` to the nearest integer, away from zero
+#[cfg(not(feature = "libm"))]
+pub(crate) fn roundf(x: f32) -> f32 {
+    F32Ext::round(x)
+}
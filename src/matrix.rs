@@ -0,0 +1,154 @@
+//! Mapping of small LED matrices onto chained LP50xx outputs
+//!
+//! `Matrix` maps (x, y) coordinates onto the RGB channels of a color mode driver, continuing across the
+//! chips of the chain, or onto the LEDs of a monochromatic `LedChain`:
+//!
+//! ```ignore
+//! let matrix: Matrix<4, 3> = Matrix::new(Layout::Serpentine);
+//! let mut pixels = [[(0xFF, [0, 0, 0]); 4]; 3];
+//! pixels[1][2] = (0xFF, [0xFF, 0, 0]);
+//! matrix.draw(&mut controller, &pixels)?;
+//! ```
+
+use crate::chain::LedChain;
+use crate::framebuffer::{MAX_CHIPS, MAX_REGISTERS};
+use crate::{ColorMode, Enabled, Error, LP50xx, Lp50xxInterface};
+use embedded_hal::digital::OutputPin;
+
+/// Maximum number of RGB channels in a chain, twelve per LP5036
+const MAX_CHANNELS: usize = MAX_CHIPS * MAX_REGISTERS / 4;
+
+/// Order in which the outputs are wired across the matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Every row runs from left to right
+    RowMajor,
+    /// Rows alternate direction, the first running from left to right
+    Serpentine,
+}
+
+/// Matrix of `W` by `H` pixels, the origin being the first output at the top left
+#[derive(Clone, Copy, Debug)]
+pub struct Matrix<const W: usize, const H: usize> {
+    layout: Layout,
+}
+
+impl<const W: usize, const H: usize> Matrix<W, H> {
+    /// Create the matrix
+    /// * `layout` - Order in which the outputs are wired
+    pub const fn new(layout: Layout) -> Self {
+        Self { layout }
+    }
+
+    /// The position of a pixel in the chain beginning at 0, None when outside of the matrix
+    /// * `x` - the column beginning at 0 on the left
+    /// * `y` - the row beginning at 0 at the top
+    pub fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= W || y >= H {
+            return None;
+        }
+        let x = match self.layout {
+            Layout::Serpentine if y % 2 == 1 => W - 1 - x,
+            _ => x,
+        };
+        Some(y * W + x)
+    }
+
+    /// Set the LED of a pixel of a monochromatic matrix
+    /// * `chain` - the chain of LEDs
+    /// * `(x, y)` - the pixel coordinates
+    /// * `value` - luminosity value
+    pub fn set_led<C: LedChain>(
+        &self,
+        chain: &mut C,
+        (x, y): (usize, usize),
+        value: u8,
+    ) -> Result<(), Error> {
+        let index = self.index(x, y).ok_or(Error::LedOutOfRange)?;
+        chain.set(index as u16 + 1, value)
+    }
+
+    /// Draw every pixel of a color matrix, writing each chip in a single transaction
+    /// * `driver` - the LP50xx driving the matrix
+    /// * `pixels` - the brightness and RGB values of each pixel, indexed by row and then column
+    pub fn draw<IFACE, EN>(
+        &self,
+        driver: &mut LP50xx<ColorMode, IFACE, EN, Enabled>,
+        pixels: &[[(u8, [u8; 3]); W]; H],
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        if W * H > MAX_CHANNELS {
+            return Err(Error::InvalidChannel);
+        }
+
+        let mut channels = [(0u8, [0u8; 3]); MAX_CHANNELS];
+        for (y, row) in pixels.iter().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                if let Some(index) = self.index(x, y) {
+                    channels[index] = *pixel;
+                }
+            }
+        }
+        driver.write_colors(&channels[..W * H])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, Matrix};
+    use crate::{Enabled, Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport recording the last write to each chip
+    #[derive(Default)]
+    struct Recorder {
+        data: [[u8; 16]; 4],
+        len: [usize; 4],
+    }
+
+    impl Lp50xxInterface for Recorder {
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+            let chip = (address - 0x14) as usize;
+            self.data[chip][..data.len()].copy_from_slice(data);
+            self.len[chip] = data.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn serpentine_rows_alternate() {
+        let matrix: Matrix<4, 3> = Matrix::new(Layout::Serpentine);
+        assert_eq!(matrix.index(0, 0), Some(0));
+        assert_eq!(matrix.index(0, 1), Some(7));
+        assert_eq!(matrix.index(3, 2), Some(11));
+        assert_eq!(matrix.index(4, 0), None);
+
+        let matrix: Matrix<4, 3> = Matrix::new(Layout::RowMajor);
+        assert_eq!(matrix.index(0, 1), Some(4));
+    }
+
+    #[test]
+    fn draw_skips_reserved_brightness_registers() {
+        let driver = LP50xx::init_with_interface(Model::LP5009, Recorder::default(), NoEnable);
+        let mut driver = driver.into_color_mode().into_state::<Enabled>();
+        driver.set_chain_length(2).unwrap();
+        let matrix: Matrix<3, 2> = Matrix::new(Layout::Serpentine);
+        let pixels = [
+            [(0x10, [1, 0, 0]), (0x20, [2, 0, 0]), (0x30, [3, 0, 0])],
+            [(0x40, [4, 0, 0]), (0x50, [5, 0, 0]), (0x60, [6, 0, 0])],
+        ];
+        matrix.draw(&mut driver, &pixels).unwrap();
+
+        let (recorder, _) = driver.release();
+        assert_eq!(
+            &recorder.data[0][..recorder.len[0]],
+            &[0x07, 0x10, 0x20, 0x30, 0, 1, 0, 0, 2, 0, 0, 3, 0, 0]
+        );
+        assert_eq!(
+            &recorder.data[1][..recorder.len[1]],
+            &[0x07, 0x60, 0x50, 0x40, 0, 6, 0, 0, 5, 0, 0, 4, 0, 0]
+        );
+    }
+}
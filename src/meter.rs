@@ -0,0 +1,295 @@
+//! Level display widgets
+//!
+//! `VuMeter` draws a green, yellow and red bar across the RGB channels of the active address:
+//!
+//! ```ignore
+//! let mut meter = VuMeter::new(200, 20).with_peak_hold(500);
+//! // For every audio block, every 10ms
+//! meter.update(&mut controller, level, 10)?;
+//! ```
+//!
+//! `BatteryIndicator` lights a set of monochromatic LEDs by battery percentage, blinking when low:
+//!
+//! ```ignore
+//! let mut battery = BatteryIndicator::new(&[1, 2, 3, 4], &[5, 25, 50, 75], 0xFF).with_low_battery(10, 500);
+//! // Every 10ms
+//! battery.update(&mut controller, percent, 10)?;
+//! ```
+
+use crate::framebuffer::MAX_REGISTERS;
+use crate::{ColorMode, Enabled, Error, LP50xx, Lp50xxInterface, MonochromaticMode};
+use embedded_hal::digital::OutputPin;
+
+const GREEN: [u8; 3] = [0, 255, 0];
+const YELLOW: [u8; 3] = [255, 255, 0];
+const RED: [u8; 3] = [255, 0, 0];
+
+/// Level meter with attack and decay smoothing and an optional peak hold channel
+pub struct VuMeter {
+    /// Smoothed level in 1/256ths of the input
+    level: u16,
+    /// Fraction of the distance to a higher input moved per update, 255 being instant
+    attack: u8,
+    /// Fraction of the distance to a lower input moved per update, 255 being instant
+    decay: u8,
+    /// Time the peak is held in milliseconds, None when peak hold is disabled
+    hold_ms: Option<u32>,
+    /// Highest level since the peak was last released
+    peak: u8,
+    /// Time since the peak was reached in milliseconds
+    peak_elapsed: u32,
+}
+
+impl VuMeter {
+    /// Create a level meter, peak hold is disabled
+    /// * `attack` - Fraction of the distance to a higher input moved per update, 255 being instant
+    /// * `decay` - Fraction of the distance to a lower input moved per update, 255 being instant
+    pub fn new(attack: u8, decay: u8) -> Self {
+        Self {
+            level: 0,
+            attack,
+            decay,
+            hold_ms: None,
+            peak: 0,
+            peak_elapsed: 0,
+        }
+    }
+
+    /// Light the channel of the highest level, holding it before it falls back to the current level
+    /// * `hold_ms` - Time the peak is held in milliseconds
+    pub fn with_peak_hold(mut self, hold_ms: u32) -> Self {
+        self.hold_ms = Some(hold_ms);
+        self
+    }
+
+    /// The smoothed level
+    pub fn level(&self) -> u8 {
+        (self.level >> 8) as u8
+    }
+
+    /// Smooth the input and draw the meter
+    /// * `driver` - the LP50xx driving the meter
+    /// * `input` - the level, 0 being silent and 255 being full scale
+    /// * `elapsed_ms` - Time elapsed since the previous update in milliseconds
+    pub fn update<IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<ColorMode, IFACE, EN, Enabled>,
+        input: u8,
+        elapsed_ms: u32,
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        self.smooth(input, elapsed_ms);
+        let channels = driver.model.get_channel_count();
+        let (brightness, colors) = self.draw(channels as usize);
+
+        let mut values = [0u8; MAX_REGISTERS];
+        for ((channel, brightness), color) in
+            (1..=channels).zip(&brightness).zip(colors.chunks_exact(3))
+        {
+            let color = [color[0], color[1], color[2]];
+            driver.encode_channel(&mut values, channel, *brightness, color);
+        }
+
+        let brightness_base = driver.model.get_brightness_base();
+        let len = driver.model.get_output_count();
+        driver.write_outputs(driver.active_address, brightness_base, &values[..len])
+    }
+
+    /// Move the level towards the input and track the peak
+    /// * `input` - the level
+    /// * `elapsed_ms` - Time elapsed since the previous update in milliseconds
+    fn smooth(&mut self, input: u8, elapsed_ms: u32) {
+        let target = (input as i32) << 8;
+        let coefficient = if target > self.level as i32 {
+            self.attack
+        } else {
+            self.decay
+        };
+        let distance = target - self.level as i32;
+        self.level = (self.level as i32 + distance * coefficient as i32 / 255) as u16;
+
+        let level = self.level();
+        self.peak_elapsed = self.peak_elapsed.saturating_add(elapsed_ms);
+        if level >= self.peak || self.peak_elapsed > self.hold_ms.unwrap_or(0) {
+            self.peak = level;
+            self.peak_elapsed = 0;
+        }
+    }
+
+    /// Brightness and color of each channel, the bar is lit proportionally to the level with the
+    /// channel at the end of the bar dimmed by the remainder
+    /// * `channels` - Number of RGB channels
+    fn draw(&self, channels: usize) -> ([u8; MAX_REGISTERS / 4], [u8; MAX_REGISTERS]) {
+        let mut brightness = [0u8; MAX_REGISTERS / 4];
+        let mut colors = [0u8; MAX_REGISTERS];
+
+        // The level in 1/255ths of a channel
+        let units = self.level() as usize * channels;
+        let (lit, remainder) = (units / 255, (units % 255) as u8);
+        let peak = match self.hold_ms {
+            Some(_) if self.peak > 0 => Some((self.peak as usize * channels).div_ceil(255) - 1),
+            _ => None,
+        };
+
+        for channel in 0..channels {
+            // Green up to 60% of the bar, yellow up to 85% and red above
+            let position = (channel + 1) * 100 / channels;
+            let color = match position {
+                0..=60 => GREEN,
+                61..=85 => YELLOW,
+                _ => RED,
+            };
+            colors[channel * 3..channel * 3 + 3].copy_from_slice(&color);
+
+            brightness[channel] = if channel < lit || peak == Some(channel) {
+                0xFF
+            } else if channel == lit {
+                remainder
+            } else {
+                0
+            };
+        }
+        (brightness, colors)
+    }
+}
+
+/// Battery indicator lighting a set of LEDs by battery percentage, with a low battery blink state
+pub struct BatteryIndicator<'a> {
+    /// LED indices, beginning at 1
+    leds: &'a [u16],
+    /// Ascending percentage required to light each LED
+    thresholds: &'a [u8],
+    /// Luminosity of the lit LEDs
+    value: u8,
+    /// Percentage at or below which the first LED blinks, and the blink half period in milliseconds
+    low: Option<(u8, u32)>,
+    /// Time elapsed in the low battery blink period in milliseconds
+    elapsed: u32,
+    /// The number of lit LEDs last written
+    last: Option<usize>,
+}
+
+impl<'a> BatteryIndicator<'a> {
+    /// Create a battery indicator, the low battery blink state is disabled
+    /// * `leds` - LED indices beginning at 1, in the order they light up
+    /// * `thresholds` - Ascending percentage required to light each LED
+    /// * `value` - Luminosity of the lit LEDs
+    pub fn new(leds: &'a [u16], thresholds: &'a [u8], value: u8) -> Self {
+        Self {
+            leds,
+            thresholds,
+            value,
+            low: None,
+            elapsed: 0,
+            last: None,
+        }
+    }
+
+    /// Blink the first LED, with every other LED off, when the battery is low
+    /// * `percent` - Percentage at or below which the battery is low
+    /// * `blink_ms` - Time spent on, and then off, in milliseconds
+    pub fn with_low_battery(mut self, percent: u8, blink_ms: u32) -> Self {
+        self.low = Some((percent, blink_ms.max(1)));
+        self
+    }
+
+    /// Update the indicator, the LEDs are only written when their state changes
+    /// * `driver` - the LP50xx driving the LEDs
+    /// * `percent` - the battery percentage
+    /// * `elapsed_ms` - Time elapsed since the previous update in milliseconds
+    pub fn update<IFACE, EN>(
+        &mut self,
+        driver: &mut LP50xx<MonochromaticMode, IFACE, EN, Enabled>,
+        percent: u8,
+        elapsed_ms: u32,
+    ) -> Result<(), Error>
+    where
+        IFACE: Lp50xxInterface,
+        EN: OutputPin,
+    {
+        let lit = match self.low {
+            Some((low, blink_ms)) if percent <= low => {
+                let period = blink_ms.saturating_mul(2);
+                self.elapsed = self.elapsed.saturating_add(elapsed_ms % period) % period;
+                (self.elapsed < blink_ms) as usize
+            }
+            _ => self
+                .thresholds
+                .iter()
+                .take_while(|t| percent >= **t)
+                .count(),
+        };
+        if self.last == Some(lit) {
+            return Ok(());
+        }
+
+        for (index, led) in self.leds.iter().enumerate() {
+            driver.set(*led, if index < lit { self.value } else { 0 })?;
+        }
+        self.last = Some(lit);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatteryIndicator, VuMeter};
+    use crate::{Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport recording the last write
+    #[derive(Default)]
+    struct Recorder {
+        data: [u8; 16],
+        len: usize,
+    }
+
+    impl Lp50xxInterface for Recorder {
+        fn write(&mut self, _address: u8, data: &[u8]) -> Result<(), Error> {
+            self.data[..data.len()].copy_from_slice(data);
+            self.len = data.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn level_attacks_and_decays() {
+        let mut meter = VuMeter::new(255, 128).with_peak_hold(100);
+        meter.smooth(200, 10);
+        assert_eq!(meter.level(), 200);
+        meter.smooth(0, 10);
+        assert_eq!(meter.level(), 99);
+
+        let (brightness, _) = meter.draw(4);
+        assert_eq!(&brightness[..4], &[0xFF, 141, 0, 0xFF]);
+    }
+
+    #[test]
+    fn meter_skips_reserved_brightness_registers() {
+        let driver = LP50xx::init_with_interface(Model::LP5009, Recorder::default(), NoEnable);
+        let mut driver = driver.into_color_mode().into_state::<crate::Enabled>();
+        let mut meter = VuMeter::new(255, 255);
+        meter.update(&mut driver, 255, 10).unwrap();
+
+        let (recorder, _) = driver.release();
+        assert_eq!(
+            &recorder.data[..recorder.len],
+            &[0x07, 0xFF, 0xFF, 0xFF, 0, 0, 255, 0, 255, 255, 0, 255, 0, 0]
+        );
+    }
+
+    #[test]
+    fn long_blink_periods_saturate() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Recorder::default(), NoEnable);
+        let mut driver = driver
+            .into_monochromatic_mode()
+            .into_state::<crate::Enabled>();
+        let mut battery =
+            BatteryIndicator::new(&[1, 2], &[5, 50], 0xFF).with_low_battery(10, u32::MAX);
+        battery.update(&mut driver, 5, u32::MAX - 1).unwrap();
+        battery.update(&mut driver, 5, u32::MAX - 1).unwrap();
+        assert_eq!(battery.last, Some(1));
+    }
+}
@@ -0,0 +1,180 @@
+//! Mock transport for host tests of firmware using the LP50xx, enabled with the `mock` feature,
+//! which links `std`
+//!
+//! ```ignore
+//! let controller = LP50xx::init_with_interface(Model::LP5012, MockInterface::new(Model::LP5012), NoEnable);
+//! let mut controller = controller.into_monochromatic_mode().enable(&mut delay)?;
+//! controller.set(1, 0xFF)?;
+//! let (mock, _) = controller.release();
+//! assert_eq!(mock.register(Address::Independent(0), registers::lp5012::OUT0_COLOR), 0xFF);
+//! ```
+
+use crate::{Address, Error, Lp50xxInterface, Model, MAX_CHIPS, MAX_REGISTER_COUNT};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use std::vec::Vec;
+
+/// A single register write recorded by the mock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Write {
+    /// 7bit address the write was sent to
+    pub address: u8,
+    /// Address of the register
+    pub register: u8,
+    /// The register value
+    pub value: u8,
+}
+
+/// Transport recording every register write and serving reads from a simulated register file of
+/// each chip. Chips are addressed consecutively from 0b00, Broadcast writes reach every chip and
+/// any other address is not acknowledged
+pub struct MockInterface {
+    model: Model,
+    chips: u8,
+    registers: [[u8; MAX_REGISTER_COUNT]; MAX_CHIPS],
+    writes: Vec<Write>,
+}
+
+impl MockInterface {
+    /// Create a mock of four chips with every register cleared
+    /// * `model` - The model of the simulated chips
+    pub fn new(model: Model) -> Self {
+        Self {
+            model,
+            chips: MAX_CHIPS as u8,
+            registers: [[0; MAX_REGISTER_COUNT]; MAX_CHIPS],
+            writes: Vec::new(),
+        }
+    }
+
+    /// Simulate fewer chips, the addresses of the missing chips are not acknowledged
+    /// * `chips` - Number of chips, between 0 and 4
+    pub fn with_chips(mut self, chips: u8) -> Self {
+        self.chips = chips.min(MAX_CHIPS as u8);
+        self
+    }
+
+    /// Every register write in the order it was received, auto-increment writes are recorded per register
+    pub fn writes(&self) -> &[Write] {
+        &self.writes
+    }
+
+    /// Forget the recorded writes, keeping the register file
+    pub fn clear_writes(&mut self) {
+        self.writes.clear();
+    }
+
+    /// Get a register of the simulated register file
+    /// * `address` - Address of the chip, Broadcast is not a chip and reads as 0
+    /// * `register` - Address of the register
+    pub fn register(&self, address: Address, register: u8) -> u8 {
+        match address {
+            Address::Independent(chip) => self
+                .registers
+                .get(chip as usize)
+                .and_then(|registers| registers.get(register as usize))
+                .copied()
+                .unwrap_or(0),
+            Address::Broadcast => 0,
+        }
+    }
+
+    /// Set a register of the simulated register file, for example to simulate a chip state for reads
+    /// * `address` - Address of the chip, Broadcast sets the register of every chip
+    /// * `register` - Address of the register
+    /// * `value` - The register value
+    pub fn set_register(&mut self, address: Address, register: u8, value: u8) {
+        for chip in self.chips_of(address) {
+            if let Some(stored) = self.registers[chip].get_mut(register as usize) {
+                *stored = value;
+            }
+        }
+    }
+
+    /// The chips reached by an address
+    fn chips_of(&self, address: Address) -> core::ops::Range<usize> {
+        match address {
+            Address::Broadcast => 0..self.chips as usize,
+            Address::Independent(chip) if chip < self.chips => chip as usize..chip as usize + 1,
+            Address::Independent(_) => 0..0,
+        }
+    }
+
+    /// Resolve a 7bit address to the simulated chip address, not acknowledging unknown addresses
+    fn resolve(&self, address: u8) -> Result<Address, Error> {
+        let broadcast = Address::Broadcast.into_u8_for_model(self.model)?;
+        let base = Address::Independent(0).into_u8_for_model(self.model)?;
+        match address.wrapping_sub(base) {
+            _ if address == broadcast => Ok(Address::Broadcast),
+            chip if chip < self.chips => Ok(Address::Independent(chip)),
+            _ => Err(Error::CommError(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address,
+            ))),
+        }
+    }
+}
+
+impl Lp50xxInterface for MockInterface {
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        let chip = self.resolve(address)?;
+        let (register, values) = data.split_first().ok_or(Error::InvalidRegister)?;
+        for (register, value) in (*register..).zip(values) {
+            self.set_register(chip, register, *value);
+            self.writes.push(Write {
+                address,
+                register,
+                value: *value,
+            });
+        }
+        Ok(())
+    }
+
+    fn write_read(&mut self, address: u8, data: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+        let chip = match self.resolve(address)? {
+            Address::Broadcast => return Err(Error::InvalidAddress),
+            chip => chip,
+        };
+        let register = *data.first().ok_or(Error::InvalidRegister)?;
+        for (register, value) in (register..).zip(buffer.iter_mut()) {
+            *value = self.register(chip, register);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockInterface, Write};
+    use crate::{registers, Address, Error, LP50xx, Model, NoEnable};
+
+    #[test]
+    fn writes_are_recorded_and_read_back() {
+        let mock = MockInterface::new(Model::LP5012).with_chips(2);
+        let mut controller = LP50xx::init_with_interface(Model::LP5012, mock, NoEnable)
+            .into_monochromatic_mode()
+            .into_state::<crate::Enabled>();
+        controller.set_chain_length(3).unwrap();
+        controller.fill(0x10).unwrap();
+        controller.set(13, 0xFF).unwrap();
+        assert!(matches!(controller.set(25, 0xFF), Err(Error::CommError(_))));
+
+        controller.set_continuous_addressing(false);
+        controller.set_active_address(Address::Independent(1));
+        assert_eq!(controller.read_out_color(1).unwrap(), 0xFF);
+        assert_eq!(controller.read_out_color(2).unwrap(), 0x10);
+
+        let (mock, _) = controller.release();
+        assert_eq!(mock.writes().len(), 13);
+        assert_eq!(
+            mock.writes()[12],
+            Write {
+                address: 0x15,
+                register: registers::lp5012::OUT0_COLOR,
+                value: 0xFF
+            }
+        );
+        assert_eq!(
+            mock.register(Address::Independent(0), registers::lp5012::OUT0_COLOR),
+            0x10
+        );
+    }
+}
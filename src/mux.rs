@@ -0,0 +1,165 @@
+//! TCA9548A I2C multiplexer support, for more than four LP50xx chips on a single bus
+//!
+//! Each multiplexer channel holds up to four LP50xx chips, driven by its own `LP50xx` through a
+//! `MuxInterface`. The multiplexer is shared between the drivers and only switches channel when a
+//! driver on another channel is addressed. The drivers are then combined into a single
+//! continuously numbered chain with `chain::Chain`.
+//!
+//! ```ignore
+//! let mux = RefCell::new(Tca9548a::new(i2c, 0x70));
+//! let first = LP50xx::init_with_interface(Model::LP5012, MuxInterface::new(&mux, 0), NoEnable);
+//! let second = LP50xx::init_with_interface(Model::LP5012, MuxInterface::new(&mux, 1), NoEnable);
+//! ```
+
+use crate::{Error, Lp50xxInterface};
+use core::cell::RefCell;
+
+/// TCA9548A 8 channel I2C multiplexer
+pub struct Tca9548a<I2C> {
+    i2c: I2C,
+    /// 7bit address of the multiplexer, 0x70 to 0x77
+    address: u8,
+    /// The channel currently selected, None until the first selection
+    selected: Option<u8>,
+}
+
+impl<I2C> Tca9548a<I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    /// Create the multiplexer
+    /// * `i2c` - I2C interface
+    /// * `address` - 7bit address of the multiplexer, 0x70 to 0x77
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self {
+            i2c,
+            address,
+            selected: None,
+        }
+    }
+
+    /// Release the underlying I2C interface
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Select a channel, if not already selected
+    /// * `channel` - the channel, 0 to 7
+    pub fn select(&mut self, channel: u8) -> Result<(), Error> {
+        if channel > 7 {
+            return Err(Error::InvalidAddress);
+        }
+        if self.selected == Some(channel) {
+            return Ok(());
+        }
+
+        // Forget the selection if the write fails, the state of the multiplexer is unknown
+        self.selected = None;
+        self.i2c
+            .write(self.address, &[1 << channel])
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))?;
+        self.selected = Some(channel);
+        Ok(())
+    }
+}
+
+/// Transport addressing the LP50xx chips on one channel of a shared TCA9548A
+pub struct MuxInterface<'a, I2C> {
+    mux: &'a RefCell<Tca9548a<I2C>>,
+    channel: u8,
+}
+
+impl<'a, I2C> MuxInterface<'a, I2C> {
+    /// Create the transport
+    /// * `mux` - the shared multiplexer
+    /// * `channel` - the multiplexer channel of the LP50xx chips, 0 to 7
+    pub fn new(mux: &'a RefCell<Tca9548a<I2C>>, channel: u8) -> Self {
+        Self { mux, channel }
+    }
+}
+
+impl<'a, I2C> Lp50xxInterface for MuxInterface<'a, I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        let mut mux = self.mux.borrow_mut();
+        mux.select(self.channel)?;
+        mux.i2c
+            .write(address, data)
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+
+    fn write_read(&mut self, address: u8, data: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+        let mut mux = self.mux.borrow_mut();
+        mux.select(self.channel)?;
+        mux.i2c
+            .write_read(address, data, buffer)
+            .map_err(|e| Error::CommError(embedded_hal::i2c::Error::kind(&e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MuxInterface, Tca9548a};
+    use crate::{Error, Lp50xxInterface};
+    use core::cell::RefCell;
+    use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
+
+    /// I2C bus recording the address and first byte of every write
+    #[derive(Default)]
+    struct Bus {
+        writes: [(u8, u8); 8],
+        len: usize,
+    }
+
+    impl ErrorType for Bus {
+        type Error = ErrorKind;
+    }
+
+    impl I2c for Bus {
+        fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                if let Operation::Write(data) = operation {
+                    self.writes[self.len] = (address, data[0]);
+                    self.len += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn channels_are_selected_on_change() {
+        let mux = RefCell::new(Tca9548a::new(Bus::default(), 0x70));
+        let mut first = MuxInterface::new(&mux, 0);
+        let mut second = MuxInterface::new(&mux, 2);
+
+        first.write(0x14, &[0x0B, 1]).unwrap();
+        first.write(0x14, &[0x0C, 2]).unwrap();
+        let mut buffer = [0u8];
+        second.write_read(0x14, &[0x00], &mut buffer).unwrap();
+        first.write(0x15, &[0x0B, 3]).unwrap();
+
+        let bus = mux.into_inner().release();
+        assert_eq!(
+            bus.writes[..bus.len],
+            [
+                (0x70, 0x01),
+                (0x14, 0x0B),
+                (0x14, 0x0C),
+                (0x70, 0x04),
+                (0x14, 0x00),
+                (0x70, 0x01),
+                (0x15, 0x0B),
+            ]
+        );
+
+        let mut mux = Tca9548a::new(Bus::default(), 0x70);
+        assert!(matches!(mux.select(8), Err(Error::InvalidAddress)));
+    }
+}
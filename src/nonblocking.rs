@@ -0,0 +1,146 @@
+//! Non-blocking writes via `nb`, enabled with the `nb` feature. For superloops that cannot block on
+//! the bus but do not want to manage the transfers themselves with the `CallbackInterface`
+
+use crate::framebuffer::MAX_REGISTERS;
+use crate::{
+    get_channel_registers, Address, ChannelIndex, ColorMode, Enabled, Error, LP50xx, LedIndex,
+    Lp50xxInterface, MonochromaticMode,
+};
+use embedded_hal::digital::OutputPin;
+
+/// A transport capable of split transactions, started and then polled until complete. The blocking
+/// writes of `Lp50xxInterface` are still used to enable and configure the LP50xx
+pub trait Lp50xxNbInterface: Lp50xxInterface {
+    /// Start writing the data payload, or poll the write already started with the same payload.
+    /// Returns `WouldBlock` until the write is complete
+    /// * `address` - 7bit address of the LP50xx
+    /// * `data` - The data payload to be sent, beginning with the register address
+    fn write_nb(&mut self, address: u8, data: &[u8]) -> nb::Result<(), Error>;
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxNbInterface,
+    EN: OutputPin,
+{
+    /// Write consecutive brightness or color registers without blocking, the shadow is updated once
+    /// the write is complete. In buffered mode only the shadow is updated
+    /// * `addr` - Address of the LP50xx
+    /// * `register` - Address of the first register
+    /// * `values` - The register values
+    fn write_outputs_nb(
+        &mut self,
+        addr: Address,
+        register: u8,
+        values: &[u8],
+    ) -> nb::Result<(), Error> {
+        if !self.buffered {
+            let mut payload = [0u8; 1 + MAX_REGISTERS];
+            payload[0] = register;
+            payload[1..=values.len()].copy_from_slice(values);
+            let address = self.get_i2c_address(addr)?;
+            self.interface
+                .write_nb(address, &payload[..=values.len()])?;
+            self.observe_write(addr, &payload[..=values.len()]);
+        }
+        Ok(self.update_frame(addr, register, values)?)
+    }
+}
+
+impl<IFACE, EN> LP50xx<ColorMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxNbInterface,
+    EN: OutputPin,
+{
+    /// Set the channel brightness and RGB values without blocking, call it again with the same values
+    /// until it no longer returns `WouldBlock`. The brightness and color registers are written in two
+    /// transactions, a brightness register already holding the value is not written again
+    /// * `channel` - the channel number (LP5009: 1-3, LP5012: 1-4, LP5018: 1-6, LP5024: 1-8, LP5030: 1-10, LP5036: 1-12)
+    /// * `value` - the channel brightness and RGB values
+    pub fn set_nb(
+        &mut self,
+        channel: impl ChannelIndex,
+        (brightness, [r, g, b]): (u8, [u8; 3]),
+    ) -> nb::Result<(), Error> {
+        let channel = channel.channel_index();
+        let (bright_addr, color_addr) = get_channel_registers(channel, self.model)?;
+        let brightness = self.scale(brightness);
+        let color = self.correct_color(channel, [r, g, b]);
+        let address = self.active_address;
+
+        let offset = (bright_addr - self.model.get_brightness_base()) as usize;
+        if self.frame.get(address, offset, 1)? != [brightness] {
+            self.write_outputs_nb(address, bright_addr, &[brightness])?;
+        }
+        self.write_outputs_nb(address, color_addr, &color)
+    }
+}
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxNbInterface,
+    EN: OutputPin,
+{
+    /// Set the desired LED value without blocking, call it again with the same value until it no
+    /// longer returns `WouldBlock`
+    /// * `led` - the LED index beginning at 1
+    /// * `value` - luminosity value
+    pub fn set_nb(&mut self, led: impl LedIndex, value: u8) -> nb::Result<(), Error> {
+        let (address, register, value) = self.get_led_write(led.led_index(), value)?;
+        self.write_outputs_nb(address, register, &[value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lp50xxNbInterface;
+    use crate::{Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport taking a number of polls to complete each write
+    struct Slow {
+        polls: u8,
+        busy: u8,
+        writes: u8,
+    }
+
+    impl Lp50xxInterface for Slow {
+        fn write(&mut self, _address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    impl Lp50xxNbInterface for Slow {
+        fn write_nb(&mut self, _address: u8, _data: &[u8]) -> nb::Result<(), Error> {
+            if self.busy < self.polls {
+                self.busy += 1;
+                return Err(nb::Error::WouldBlock);
+            }
+            self.busy = 0;
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn set_completes_after_polling() {
+        let slow = Slow {
+            polls: 2,
+            busy: 0,
+            writes: 0,
+        };
+        let mut controller = LP50xx::init_with_interface(Model::LP5012, slow, NoEnable)
+            .into_color_mode()
+            .into_state::<crate::Enabled>();
+
+        let mut polls = 0;
+        while let Err(nb::Error::WouldBlock) = controller.set_nb(1, (0xFF, [1, 2, 3])) {
+            polls += 1;
+        }
+        assert_eq!(polls, 4);
+        // The brightness is already written, only the color is written again
+        assert!(nb::block!(controller.set_nb(1, (0xFF, [3, 2, 1]))).is_ok());
+        let (slow, _) = controller.release();
+        assert_eq!(slow.writes, 3);
+    }
+}
@@ -0,0 +1,133 @@
+//! Command queue separating the tasks requesting LED changes from the task owning the bus, enabled
+//! with the `heapless` feature
+//!
+//! With RTIC, split the queue in `init` and give the `Commands` to the high priority tasks and the
+//! `Worker` to the task owning the LP50xx:
+//!
+//! ```ignore
+//! let queue: &'static mut CommandQueue<u8, 16> = singleton!(: CommandQueue<u8, 16> = CommandQueue::new()).unwrap();
+//! let (mut commands, mut worker) = queue.split();
+//! // From any task
+//! commands.set(5, 0xFF)?;
+//! // From the task owning the bus
+//! worker.drain(&mut controller)?;
+//! ```
+
+use crate::animation::Animate;
+use crate::Error;
+use heapless::spsc::{Consumer, Producer, Queue};
+
+/// Queue of output values, holding up to `N - 1` commands
+pub struct CommandQueue<V, const N: usize> {
+    queue: Queue<(u16, V), N>,
+}
+
+impl<V, const N: usize> Default for CommandQueue<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, const N: usize> CommandQueue<V, N> {
+    pub const fn new() -> Self {
+        Self {
+            queue: Queue::new(),
+        }
+    }
+
+    /// Split the queue into the producer of commands and the consumer writing them to the LP50xx
+    pub fn split(&mut self) -> (Commands<'_, V, N>, Worker<'_, V, N>) {
+        let (producer, consumer) = self.queue.split();
+        (Commands { producer }, Worker { consumer })
+    }
+}
+
+/// Producer of commands, which does not need access to the bus
+pub struct Commands<'a, V, const N: usize> {
+    producer: Producer<'a, (u16, V), N>,
+}
+
+impl<V, const N: usize> Commands<'_, V, N> {
+    /// Request an output change, returns `Error::NoFreeSlot` when the queue is full
+    /// * `output` - the channel in color mode, or the LED index in monochromatic mode
+    /// * `value` - the output value
+    pub fn set(&mut self, output: u16, value: V) -> Result<(), Error> {
+        self.producer
+            .enqueue((output, value))
+            .map_err(|_| Error::NoFreeSlot)
+    }
+
+    /// Whether another command can be queued
+    pub fn ready(&self) -> bool {
+        self.producer.ready()
+    }
+}
+
+/// Consumer of commands, writing them to the LP50xx
+pub struct Worker<'a, V, const N: usize> {
+    consumer: Consumer<'a, (u16, V), N>,
+}
+
+impl<V, const N: usize> Worker<'_, V, N> {
+    /// Write every queued command to the LP50xx, in the order they were queued. A command which fails
+    /// to be written is dropped and the error returned, the remaining commands stay queued
+    /// * `driver` - the LP50xx driving the outputs
+    pub fn drain<D>(&mut self, driver: &mut D) -> Result<(), Error>
+    where
+        D: Animate<Value = V>,
+    {
+        while let Some((output, value)) = self.consumer.dequeue() {
+            driver.apply(output, value)?;
+        }
+        Ok(())
+    }
+
+    /// Number of queued commands
+    pub fn len(&self) -> usize {
+        self.consumer.len()
+    }
+
+    /// No command is queued
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandQueue;
+    use crate::{Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport counting the writes
+    #[derive(Default)]
+    struct Counter {
+        writes: usize,
+    }
+
+    impl Lp50xxInterface for Counter {
+        fn write(&mut self, _address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn commands_are_drained_onto_the_bus() {
+        let mut controller =
+            LP50xx::init_with_interface(Model::LP5012, Counter::default(), NoEnable)
+                .into_monochromatic_mode()
+                .into_state::<crate::Enabled>();
+
+        let mut queue: CommandQueue<u8, 3> = CommandQueue::new();
+        let (mut commands, mut worker) = queue.split();
+        commands.set(1, 0xFF).unwrap();
+        commands.set(2, 0x80).unwrap();
+        assert!(matches!(commands.set(3, 0x10), Err(Error::NoFreeSlot)));
+        assert_eq!(worker.len(), 2);
+
+        worker.drain(&mut controller).unwrap();
+        assert!(worker.is_empty());
+        let (counter, _) = controller.release();
+        assert_eq!(counter.writes, 2);
+    }
+}
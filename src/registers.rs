@@ -0,0 +1,52 @@
+//! Register map of the LP50xx family, for raw register access with `write_register` and `read_register`.
+//! The registers shared by every model are defined here, the remainder in the module of each model pair
+
+/// DEVICE_CONFIG0, holds the CHIP_EN bit
+pub const DEVICE_CONFIG0: u8 = 0x00;
+/// CHIP_EN bit of DEVICE_CONFIG0, enabling the LP50xx
+pub const CHIP_EN: u8 = 0b0100_0000;
+/// DEVICE_CONFIG1, see `DeviceConfig`
+pub const DEVICE_CONFIG1: u8 = 0x01;
+/// LED_CONFIG0, assigning channels 0 to 7 to the bank
+pub const LED_CONFIG0: u8 = 0x02;
+/// LED_CONFIG1, assigning channels 8 to 11 to the bank on the LP5030 and LP5036
+pub const LED_CONFIG1: u8 = 0x03;
+
+/// Registers of the LP5009 and LP5012
+pub mod lp5012 {
+    pub const BANK_BRIGHTNESS: u8 = 0x03;
+    pub const BANK_A_COLOR: u8 = 0x04;
+    pub const BANK_B_COLOR: u8 = 0x05;
+    pub const BANK_C_COLOR: u8 = 0x06;
+    /// LED0_BRIGHTNESS, the brightness registers of the following channels are consecutive
+    pub const LED0_BRIGHTNESS: u8 = 0x07;
+    /// OUT0_COLOR, the color registers of the following pins are consecutive
+    pub const OUT0_COLOR: u8 = 0x0B;
+    pub const RESET: u8 = 0x17;
+}
+
+/// Registers of the LP5018 and LP5024
+pub mod lp5024 {
+    pub const BANK_BRIGHTNESS: u8 = 0x03;
+    pub const BANK_A_COLOR: u8 = 0x04;
+    pub const BANK_B_COLOR: u8 = 0x05;
+    pub const BANK_C_COLOR: u8 = 0x06;
+    /// LED0_BRIGHTNESS, the brightness registers of the following channels are consecutive
+    pub const LED0_BRIGHTNESS: u8 = 0x07;
+    /// OUT0_COLOR, the color registers of the following pins are consecutive
+    pub const OUT0_COLOR: u8 = 0x0F;
+    pub const RESET: u8 = 0x27;
+}
+
+/// Registers of the LP5030 and LP5036
+pub mod lp5036 {
+    pub const BANK_BRIGHTNESS: u8 = 0x04;
+    pub const BANK_A_COLOR: u8 = 0x05;
+    pub const BANK_B_COLOR: u8 = 0x06;
+    pub const BANK_C_COLOR: u8 = 0x07;
+    /// LED0_BRIGHTNESS, the brightness registers of the following channels are consecutive
+    pub const LED0_BRIGHTNESS: u8 = 0x08;
+    /// OUT0_COLOR, the color registers of the following pins are consecutive
+    pub const OUT0_COLOR: u8 = 0x14;
+    pub const RESET: u8 = 0x38;
+}
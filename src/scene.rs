@@ -0,0 +1,113 @@
+//! Snapshots of the output state, for menu systems and notifications which temporarily override
+//! the LEDs and then restore them exactly
+//!
+//! ```ignore
+//! let scene = controller.snapshot();
+//! controller.fill(0xFF)?;
+//! // Once the notification is dismissed
+//! controller.apply(&scene)?;
+//! ```
+
+use crate::framebuffer::{MAX_CHIPS, MAX_REGISTERS};
+use crate::{Address, Enabled, Error, LP50xx, Lp50xxInterface};
+use embedded_hal::digital::OutputPin;
+
+/// The brightness and color registers of every chip in the chain, as last written, and the brightness factor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scene {
+    registers: [[u8; MAX_REGISTERS]; MAX_CHIPS],
+    chain_length: u8,
+    brightness_factor: f32,
+}
+
+impl<MODE, IFACE, EN, STATE> LP50xx<MODE, IFACE, EN, STATE> {
+    /// Capture the brightness and color registers of every chip in the chain, including writes
+    /// pending a flush in buffered mode, and the brightness factor
+    pub fn snapshot(&self) -> Scene {
+        let mut registers = [[0u8; MAX_REGISTERS]; MAX_CHIPS];
+        for (chip, values) in registers.iter_mut().enumerate() {
+            if let Ok(frame) = self
+                .frame
+                .get(Address::Independent(chip as u8), 0, MAX_REGISTERS)
+            {
+                values.copy_from_slice(frame);
+            }
+        }
+
+        Scene {
+            registers,
+            chain_length: self.chain_length,
+            brightness_factor: self.brightness_factor,
+        }
+    }
+}
+
+impl<MODE, IFACE, EN> LP50xx<MODE, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Restore the brightness and color registers and the brightness factor of a snapshot. The registers
+    /// of each chip are written in a single auto-increment transaction, deferred until the next flush in
+    /// buffered mode. The values were scaled when first written, so they are restored unchanged
+    /// * `scene` - The snapshot to restore
+    pub fn apply(&mut self, scene: &Scene) -> Result<(), Error> {
+        let base = self.model.get_brightness_base();
+        let len = self.model.get_output_count();
+        for (chip, values) in (0..scene.chain_length).zip(&scene.registers) {
+            self.write_outputs(Address::Independent(chip), base, &values[..len])?;
+        }
+        self.brightness_factor = scene.brightness_factor;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Enabled, Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport counting the writes to each chip
+    #[derive(Default)]
+    struct Counter {
+        writes: [usize; 4],
+    }
+
+    impl Lp50xxInterface for Counter {
+        fn write(&mut self, address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.writes[(address - 0x14) as usize] += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn apply_restores_snapshot() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Counter::default(), NoEnable);
+        let mut driver = driver.into_monochromatic_mode().into_state::<Enabled>();
+        driver.set_chain_length(2).unwrap();
+        driver.set(1, 0x40).unwrap();
+        driver.set(13, 0x80).unwrap();
+        let scene = driver.snapshot();
+
+        driver.set_brightness_factor(0.5);
+        driver.set(13, 0xFF).unwrap();
+        assert_ne!(driver.snapshot(), scene);
+        driver.apply(&scene).unwrap();
+        assert_eq!(driver.snapshot(), scene);
+
+        let (counter, _) = driver.release();
+        assert_eq!(counter.writes, [2, 3, 0, 0]);
+    }
+
+    #[test]
+    fn apply_restores_the_last_output() {
+        let driver = LP50xx::init_with_interface(Model::LP5009, Counter::default(), NoEnable);
+        let mut driver = driver.into_monochromatic_mode().into_state::<Enabled>();
+        driver.set_chain_length(1).unwrap();
+        driver.set(9, 0x80).unwrap();
+        let scene = driver.snapshot();
+
+        driver.set(9, 0).unwrap();
+        driver.apply(&scene).unwrap();
+        assert_eq!(driver.snapshot(), scene);
+    }
+}
@@ -0,0 +1,213 @@
+//! Register geometry of LP50xx family members
+//!
+//! The built-in `Model`s implement `Lp50xxVariant`. Unreleased or customer-specific family members are
+//! described by implementing the trait and converting the description into a `Model`:
+//!
+//! ```ignore
+//! struct Lp5015;
+//!
+//! impl Lp50xxVariant for Lp5015 {
+//!     fn pin_count(&self) -> u8 { 15 }
+//!     // ...
+//! }
+//!
+//! let model = Model::from_variant(&Lp5015)?;
+//! let controller = LP50xx::init_with_i2c(model, i2c, en);
+//! ```
+
+use crate::framebuffer::MAX_REGISTERS;
+use crate::{Error, MAX_REGISTER_COUNT};
+
+/// Pin count, register offsets and addresses of an LP50xx family member
+pub trait Lp50xxVariant {
+    /// Number of OUTx pins, three per RGB channel
+    fn pin_count(&self) -> u8;
+
+    /// Address of the LED0_BRIGHTNESS register
+    fn brightness_base(&self) -> u8;
+
+    /// Address of the OUT0_COLOR register, after the LEDx_BRIGHTNESS registers. Reserved LEDx_BRIGHTNESS
+    /// registers may lie in between, as on the LP5009, LP5018 and LP5030
+    fn color_base(&self) -> u8;
+
+    /// Address of the BANK_BRIGHTNESS register, followed by BANK_A_COLOR, BANK_B_COLOR and BANK_C_COLOR
+    fn bank_brightness_register(&self) -> u8;
+
+    /// Address of the RESET register, the last register of the register file
+    fn reset_register(&self) -> u8;
+
+    /// 7bit base address used for independent addressing, combined with the ADDR pin state
+    fn independent_address_base(&self) -> u8;
+
+    /// 7bit broadcast address
+    fn broadcast_address(&self) -> u8;
+}
+
+/// A family member described through `Lp50xxVariant`, held by `Model::Custom`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Variant {
+    pub(crate) pin_count: u8,
+    pub(crate) brightness_base: u8,
+    pub(crate) color_base: u8,
+    pub(crate) bank_brightness_register: u8,
+    pub(crate) reset_register: u8,
+    pub(crate) independent_address_base: u8,
+    pub(crate) broadcast_address: u8,
+}
+
+impl Variant {
+    /// Capture the geometry of a family member, checking it fits the register buffers of the driver:
+    /// whole RGB channels, the OUTx_COLOR registers after the LEDx_BRIGHTNESS registers, at most 48
+    /// registers from LED0_BRIGHTNESS to the last OUTx_COLOR register and at most 57 registers
+    /// * `variant` - The description of the family member
+    pub fn new<V: Lp50xxVariant>(variant: &V) -> Result<Self, Error> {
+        let pins = variant.pin_count();
+        if pins == 0 || !pins.is_multiple_of(3) || pins as usize + pins as usize / 3 > MAX_REGISTERS
+        {
+            return Err(Error::InvalidLed);
+        }
+        let (brightness_base, color_base) = (variant.brightness_base(), variant.color_base());
+        if (color_base as usize) < brightness_base as usize + pins as usize / 3
+            || (color_base - brightness_base) as usize + pins as usize > MAX_REGISTERS
+        {
+            return Err(Error::InvalidRegister);
+        }
+        if variant.reset_register() as usize >= MAX_REGISTER_COUNT {
+            return Err(Error::InvalidRegister);
+        }
+
+        Ok(Self {
+            pin_count: pins,
+            brightness_base,
+            color_base,
+            bank_brightness_register: variant.bank_brightness_register(),
+            reset_register: variant.reset_register(),
+            independent_address_base: variant.independent_address_base(),
+            broadcast_address: variant.broadcast_address(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lp50xxVariant;
+    use crate::{Address, Error, Model};
+
+    /// A hypothetical 15 pin family member
+    struct Lp5015;
+
+    impl Lp50xxVariant for Lp5015 {
+        fn pin_count(&self) -> u8 {
+            15
+        }
+
+        fn brightness_base(&self) -> u8 {
+            0x07
+        }
+
+        fn color_base(&self) -> u8 {
+            0x0C
+        }
+
+        fn bank_brightness_register(&self) -> u8 {
+            0x03
+        }
+
+        fn reset_register(&self) -> u8 {
+            0x1B
+        }
+
+        fn independent_address_base(&self) -> u8 {
+            0x40
+        }
+
+        fn broadcast_address(&self) -> u8 {
+            0x0E
+        }
+    }
+
+    #[test]
+    fn custom_variants_are_models() {
+        let model = Model::from_variant(&Lp5015).unwrap();
+        assert_eq!(
+            Address::Independent(1).into_u8_for_model(model).unwrap(),
+            0x41
+        );
+        assert_eq!(Address::Broadcast.into_u8_for_model(model).unwrap(), 0x0E);
+        assert_eq!(model.color_base(), 0x0C);
+        assert_eq!(Model::LP5024.pin_count(), 24);
+
+        /// A family member too large for the register buffers
+        struct Huge;
+
+        impl Lp50xxVariant for Huge {
+            fn pin_count(&self) -> u8 {
+                48
+            }
+
+            fn brightness_base(&self) -> u8 {
+                Lp5015.brightness_base()
+            }
+
+            fn color_base(&self) -> u8 {
+                Lp5015.color_base()
+            }
+
+            fn bank_brightness_register(&self) -> u8 {
+                Lp5015.bank_brightness_register()
+            }
+
+            fn reset_register(&self) -> u8 {
+                Lp5015.reset_register()
+            }
+
+            fn independent_address_base(&self) -> u8 {
+                Lp5015.independent_address_base()
+            }
+
+            fn broadcast_address(&self) -> u8 {
+                Lp5015.broadcast_address()
+            }
+        }
+        assert!(matches!(Model::from_variant(&Huge), Err(Error::InvalidLed)));
+
+        /// A family member whose color registers overlap the brightness registers
+        struct Overlapping;
+
+        impl Lp50xxVariant for Overlapping {
+            fn pin_count(&self) -> u8 {
+                Lp5015.pin_count()
+            }
+
+            fn brightness_base(&self) -> u8 {
+                Lp5015.brightness_base()
+            }
+
+            fn color_base(&self) -> u8 {
+                0x0B
+            }
+
+            fn bank_brightness_register(&self) -> u8 {
+                Lp5015.bank_brightness_register()
+            }
+
+            fn reset_register(&self) -> u8 {
+                Lp5015.reset_register()
+            }
+
+            fn independent_address_base(&self) -> u8 {
+                Lp5015.independent_address_base()
+            }
+
+            fn broadcast_address(&self) -> u8 {
+                Lp5015.broadcast_address()
+            }
+        }
+        assert!(matches!(
+            Model::from_variant(&Overlapping),
+            Err(Error::InvalidRegister)
+        ));
+    }
+}
@@ -0,0 +1,147 @@
+//! Slice-like access to the cached LED values
+//!
+//! `LP50xx::leds` borrows the shadow of the OUTx_COLOR registers of a monochromatic driver as an
+//! `LedView`, so a frame is built with ordinary indexing and iteration and then written with `flush`:
+//!
+//! ```ignore
+//! controller.set_buffered(true);
+//! let mut leds = controller.leds();
+//! leds[0] = 0xFF;
+//! leds.iter_mut().skip(4).for_each(|led| *led /= 2);
+//! controller.flush()?;
+//! ```
+
+use crate::{Address, Enabled, LP50xx, MonochromaticMode};
+use core::ops::{Index, IndexMut};
+
+/// Mutable view of the cached register values of every LED, indexed from 0 for LED 1. Values are
+/// register values, without the gamma correction and brightness factor applied by the setters.
+/// Every modified LED is written by the next `flush`
+pub struct LedView<'a, IFACE, EN> {
+    driver: &'a mut LP50xx<MonochromaticMode, IFACE, EN, Enabled>,
+}
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled> {
+    /// Borrow the cached LED values as a slice-like view, see `LedView`
+    pub fn leds(&mut self) -> LedView<'_, IFACE, EN> {
+        LedView { driver: self }
+    }
+}
+
+impl<IFACE, EN> LedView<'_, IFACE, EN> {
+    /// Number of LEDs in the view
+    pub fn len(&self) -> usize {
+        self.driver.led_count() as usize
+    }
+
+    /// Whether the view holds no LEDs
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the LED values
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.len()).map(move |index| self[index])
+    }
+
+    /// Iterate over the LED values for modification, marking every LED as pending a write. Masked LEDs are
+    /// included, so their values should be left unchanged
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut u8> {
+        let driver = &mut *self.driver;
+        let (first, count) = if driver.continuous_addressing {
+            (Address::Independent(0), driver.chain_length as usize)
+        } else {
+            (driver.active_address, 1)
+        };
+        let (led_base, leds) = driver.get_led_registers();
+        let offset = (led_base - driver.model.get_brightness_base()) as usize;
+
+        driver
+            .frame
+            .get_chain_mut(first, count, offset, leds as usize)
+            .into_iter()
+            .flatten()
+            .flatten()
+    }
+
+    /// Resolve the address and offset from the first LEDx_BRIGHTNESS register of an LED
+    /// * `index` - the LED index beginning at 0
+    fn locate(&self, index: usize) -> (Address, usize) {
+        let led = if index < self.len() { index + 1 } else { 0 };
+        match self.driver.get_led_target(led as u16) {
+            Ok((address, register)) => (
+                address,
+                (register - self.driver.model.get_brightness_base()) as usize,
+            ),
+            Err(_) => panic!("LED index {} out of range", index),
+        }
+    }
+}
+
+impl<IFACE, EN> Index<usize> for LedView<'_, IFACE, EN> {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        let (address, offset) = self.locate(index);
+        match self.driver.frame.get(address, offset, 1) {
+            Ok(values) => &values[0],
+            Err(_) => panic!("LED index {} out of range", index),
+        }
+    }
+}
+
+impl<IFACE, EN> IndexMut<usize> for LedView<'_, IFACE, EN> {
+    fn index_mut(&mut self, index: usize) -> &mut u8 {
+        let (address, offset) = self.locate(index);
+        if self.driver.is_led_masked(index as u16 + 1) {
+            panic!("LED index {} is masked", index);
+        }
+        match self.driver.frame.get_mut(address, offset) {
+            Ok(value) => value,
+            Err(_) => panic!("LED index {} out of range", index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Enabled, Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport recording the address and length of every write
+    #[derive(Default)]
+    struct Recorder {
+        writes: [(u8, usize); 4],
+        len: usize,
+    }
+
+    impl Lp50xxInterface for Recorder {
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+            self.writes[self.len] = (address, data.len());
+            self.len += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn modified_leds_are_flushed() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Recorder::default(), NoEnable);
+        let mut driver = driver.into_monochromatic_mode().into_state::<Enabled>();
+        driver.set_chain_length(2).unwrap();
+        driver.set_buffered(true);
+
+        let mut leds = driver.leds();
+        assert_eq!(leds.len(), 24);
+        leds[0] = 0x10;
+        leds[13] = 0x20;
+        assert_eq!(leds.iter().map(u32::from).sum::<u32>(), 0x30);
+        driver.flush().unwrap();
+
+        driver.leds().iter_mut().for_each(|led| *led = 0xFF);
+        driver.flush().unwrap();
+        let (recorder, _) = driver.release();
+        assert_eq!(
+            recorder.writes,
+            [(0x14, 2), (0x15, 2), (0x14, 13), (0x15, 13)]
+        );
+    }
+}
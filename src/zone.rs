@@ -0,0 +1,187 @@
+//! Named groups of monochromatic LEDs
+//!
+//! A `Zone` names a set of LED indices, which may span several chips with continuous addressing, so
+//! firmware addresses the status LEDs or the keypad backlight rather than raw indices:
+//!
+//! ```ignore
+//! const KEYPAD: Zone = Zone::new("keypad backlight", &[1, 2, 3, 13, 14, 15]);
+//! controller.set_zone(&KEYPAD, 0x80)?;
+//! controller.fade_zone(&KEYPAD, 0, 500, &mut delay)?;
+//! ```
+
+use crate::animation::{breath, Blend};
+use crate::{Enabled, Error, LP50xx, Lp50xxInterface, MonochromaticMode};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+/// A named set of LEDs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Zone<'a> {
+    name: &'a str,
+    leds: &'a [u16],
+}
+
+impl<'a> Zone<'a> {
+    /// Create a zone
+    /// * `name` - Name of the zone, such as "status"
+    /// * `leds` - the LED indices beginning at 1
+    pub const fn new(name: &'a str, leds: &'a [u16]) -> Self {
+        Self { name, leds }
+    }
+
+    /// The name of the zone
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The LED indices of the zone
+    pub fn leds(&self) -> &'a [u16] {
+        self.leds
+    }
+
+    /// Whether the LED belongs to the zone
+    /// * `led` - the LED index beginning at 1
+    pub fn contains(&self, led: u16) -> bool {
+        self.leds.contains(&led)
+    }
+}
+
+impl<IFACE, EN> LP50xx<MonochromaticMode, IFACE, EN, Enabled>
+where
+    IFACE: Lp50xxInterface,
+    EN: OutputPin,
+{
+    /// Set every LED of the zone to the same value. Every LED is checked before any is written
+    /// * `zone` - the LEDs to set
+    /// * `value` - luminosity value
+    pub fn set_zone(&mut self, zone: &Zone, value: u8) -> Result<(), Error> {
+        for led in zone.leds {
+            self.get_led_output(*led)?;
+        }
+        for led in zone.leds {
+            let (address, register, value) = self.get_led_write(*led, value)?;
+            self.write_outputs(address, register, &[value])?;
+        }
+        Ok(())
+    }
+
+    /// Fade every LED of the zone from its current luminosity to the target, blocking until complete.
+    /// The LEDs are stepped together so every LED arrives at the same time
+    /// * `zone` - the LEDs to fade
+    /// * `target` - the target luminosity value
+    /// * `duration_ms` - Duration of the fade in milliseconds
+    /// * `delay` - Delay provider
+    pub fn fade_zone<DELAY: DelayNs>(
+        &mut self,
+        zone: &Zone,
+        target: u8,
+        duration_ms: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        for led in zone.leds {
+            self.get_led_output(*led)?;
+        }
+        let steps = self.fade_steps;
+        let step_us = duration_ms.saturating_mul(1000) / steps as u32;
+        let base = self.model.get_brightness_base();
+
+        for remaining in (1..=steps).rev() {
+            for led in zone.leds {
+                let (address, register, target) = self.get_led_write(*led, target)?;
+                let current = self.frame.get(address, (register - base) as usize, 1)?[0];
+
+                // Move a fraction of the remaining distance, so the last step always lands on the target
+                let distance = target as i32 - current as i32;
+                let value = (current as i32 + distance / remaining as i32) as u8;
+                self.write_outputs(address, register, &[value])?;
+            }
+
+            if self.buffered {
+                self.flush()?;
+            }
+            delay.delay_us(step_us);
+        }
+        Ok(())
+    }
+
+    /// Breathe every LED of the zone in unison between two luminosity values, blocking until complete
+    /// * `zone` - the LEDs to breathe
+    /// * `period_ms` - Duration of a single breath in milliseconds
+    /// * `min` - the luminosity at the start and end of each breath
+    /// * `max` - the luminosity in the middle of each breath
+    /// * `cycles` - Number of breaths
+    /// * `delay` - Delay provider
+    pub fn breathe_zone<DELAY: DelayNs>(
+        &mut self,
+        zone: &Zone,
+        period_ms: u32,
+        min: u8,
+        max: u8,
+        cycles: u16,
+        delay: &mut DELAY,
+    ) -> Result<(), Error> {
+        let steps = self.fade_steps as u32;
+        let step_us = period_ms.saturating_mul(1000) / steps;
+
+        for _ in 0..cycles {
+            for step in 0..steps {
+                self.set_zone(zone, u8::blend(min, max, breath(step, steps)))?;
+
+                if self.buffered {
+                    self.flush()?;
+                }
+                delay.delay_us(step_us);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zone;
+    use crate::{Enabled, Error, LP50xx, Lp50xxInterface, Model, NoEnable};
+
+    /// Transport counting the writes
+    #[derive(Default)]
+    struct Counter {
+        writes: usize,
+    }
+
+    impl Lp50xxInterface for Counter {
+        fn write(&mut self, _address: u8, _data: &[u8]) -> Result<(), Error> {
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    /// Delay provider stub
+    struct Delay;
+
+    impl embedded_hal::delay::DelayNs for Delay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn zones_span_chips() {
+        let driver = LP50xx::init_with_interface(Model::LP5012, Counter::default(), NoEnable);
+        let mut driver = driver.into_monochromatic_mode().into_state::<Enabled>();
+        driver.set_chain_length(2).unwrap();
+        let zone = Zone::new("status", &[2, 14]);
+        assert!(zone.contains(14) && !zone.contains(1));
+
+        driver.set_zone(&zone, 0x80).unwrap();
+        let scene = driver.snapshot();
+        let mut delay = Delay;
+        driver.fade_zone(&zone, 0x80, 10, &mut delay).unwrap();
+        assert_eq!(driver.snapshot(), scene);
+
+        let invalid = Zone::new("invalid", &[1, 0]);
+        assert!(matches!(
+            driver.set_zone(&invalid, 0xFF),
+            Err(Error::InvalidLed)
+        ));
+        let (counter, _) = driver.release();
+        assert_eq!(counter.writes, 2 + 2 * 32);
+    }
+}